@@ -0,0 +1,148 @@
+use crate::{error::*, traits::AsBytes};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+use core::{fmt, str::FromStr};
+
+use digest::{consts::U32, DynDigest};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! hash_algorithms {
+    ($($variant:ident => $name:literal, $ty:ty,)*) => {
+        /// # A hash algorithm chosen at runtime, e.g. from a config.
+        ///
+        /// Parsed from and displayed as its name (`"sha256"`, `"sha3-256"`, `"blake3"`, ...),
+        /// case-insensitive. serde uses the name.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum HashAlgorithm {
+            $(
+                #[doc = concat!("`", $name, "`")]
+                $variant,
+            )*
+        }
+
+        impl HashAlgorithm {
+            pub const ALL: &'static [HashAlgorithm] = &[$(HashAlgorithm::$variant,)*];
+
+            #[inline]
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(HashAlgorithm::$variant => $name,)*
+                }
+            }
+            /// A new hasher of this algorithm.
+            #[inline]
+            pub fn hasher(&self) -> Box<dyn DynDigest> {
+                match self {
+                    $(HashAlgorithm::$variant => Box::new(<$ty>::default()),)*
+                }
+            }
+        }
+    };
+}
+
+hash_algorithms! {
+    Sha224 => "sha224", sha2::Sha224,
+    Sha256 => "sha256", sha2::Sha256,
+    Sha384 => "sha384", sha2::Sha384,
+    Sha512 => "sha512", sha2::Sha512,
+    Sha512_256 => "sha512-256", sha2::Sha512_256,
+
+    Sha3_224 => "sha3-224", sha3::Sha3_224,
+    Sha3_256 => "sha3-256", sha3::Sha3_256,
+    Sha3_384 => "sha3-384", sha3::Sha3_384,
+    Sha3_512 => "sha3-512", sha3::Sha3_512,
+    Keccak256 => "keccak256", sha3::Keccak256,
+
+    Blake2b => "blake2b", blake2::Blake2b512,
+    Blake2b256 => "blake2b-256", blake2::Blake2b<U32>,
+    Blake2s => "blake2s", blake2::Blake2s256,
+
+    Blake3 => "blake3", blake3::Hasher,
+}
+
+impl HashAlgorithm {
+    #[inline]
+    pub fn output_size(&self) -> usize {
+        self.hasher().output_size()
+    }
+    /// Hash raw bytes.
+    #[inline]
+    pub fn digest(&self, bytes: impl AsBytes) -> Vec<u8> {
+        let mut hasher = self.hasher();
+        hasher.update(bytes.as_byte_slice());
+        hasher.finalize().into_vec()
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = Error;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        HashAlgorithm::ALL
+            .iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::UnknownHashAlgorithm(s.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for HashAlgorithm {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for HashAlgorithm {
+    #[inline]
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> core::result::Result<Self, De::Error> {
+        let name = alloc::string::String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Digest;
+
+    #[test]
+    fn test_hash_algorithm() {
+        for algorithm in HashAlgorithm::ALL {
+            assert_eq!(
+                algorithm.name().parse::<HashAlgorithm>().unwrap(),
+                *algorithm
+            );
+            assert_eq!(algorithm.digest(b"hello").len(), algorithm.output_size());
+        }
+        assert_eq!(
+            "SHA256".parse::<HashAlgorithm>().unwrap(),
+            HashAlgorithm::Sha256
+        );
+        assert!(matches!(
+            "md5".parse::<HashAlgorithm>(),
+            Err(Error::UnknownHashAlgorithm(_))
+        ));
+
+        assert_eq!(
+            HashAlgorithm::Sha256.digest(b"hello"),
+            sha2::Sha256::digest(b"hello").to_vec()
+        );
+        assert_eq!(
+            HashAlgorithm::Blake3.digest(b"hello"),
+            blake3::hash(b"hello").as_bytes()
+        );
+        assert_eq!(HashAlgorithm::Blake2b.output_size(), 64);
+    }
+}
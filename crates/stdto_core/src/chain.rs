@@ -0,0 +1,239 @@
+//! # Tamper-evident hash chains over `ToHash` entries.
+//!
+//! - entry hash = `D(prev || bytes)`, the same bytes as `to_hash`
+//! - `prev` of the first entry is all zeros.
+
+use crate::{error::*, traits::*};
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData};
+
+use digest::{Digest, Output};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// # One entry with the hash of the entry before it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainEntry<T> {
+    pub prev: Vec<u8>,
+    pub value: T,
+}
+
+impl<T: ToHash + ToBytes + Serialize> ChainEntry<T> {
+    #[inline]
+    pub fn try_hash<D: Digest>(&self) -> Result<Output<D>> {
+        let mut hasher = D::new();
+        hasher.update(&self.prev);
+        self.value.try_to_hash_into(&mut hasher)?;
+        Ok(hasher.finalize())
+    }
+    #[inline]
+    pub fn hash<D: Digest>(&self) -> Output<D> {
+        self.try_hash::<D>().unwrap()
+    }
+}
+
+/// # An append-only chain of entries linked by `D` hashes.
+///
+/// Serialized as the list of entries with `ToBytes` and `ToJson`.
+/// Call `verify` after deserializing.
+pub struct HashChain<T, D: Digest> {
+    entries: Vec<ChainEntry<T>>,
+    _digest: PhantomData<fn() -> D>,
+}
+
+impl<T, D: Digest> HashChain<T, D> {
+    #[inline]
+    pub fn new() -> Self {
+        HashChain {
+            entries: Vec::new(),
+            _digest: PhantomData,
+        }
+    }
+    /// Unverified, call `verify`.
+    #[inline]
+    pub fn from_entries(entries: Vec<ChainEntry<T>>) -> Self {
+        HashChain {
+            entries,
+            _digest: PhantomData,
+        }
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    #[inline]
+    pub fn entries(&self) -> &[ChainEntry<T>] {
+        &self.entries
+    }
+    #[inline]
+    pub fn into_entries(self) -> Vec<ChainEntry<T>> {
+        self.entries
+    }
+}
+
+impl<T: ToHash + ToBytes + Serialize, D: Digest> HashChain<T, D> {
+    /// The hash of the last entry, or all zeros when empty.
+    #[inline]
+    pub fn try_head(&self) -> Result<Output<D>> {
+        match self.entries.last() {
+            Some(entry) => entry.try_hash::<D>(),
+            None => Ok(Output::<D>::default()),
+        }
+    }
+    #[inline]
+    pub fn head(&self) -> Output<D> {
+        self.try_head().unwrap()
+    }
+
+    /// Append a value linked to the current head, and return the new head.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<Output<D>> {
+        let prev = self.try_head()?.to_vec();
+        let entry = ChainEntry { prev, value };
+        let head = entry.try_hash::<D>()?;
+        self.entries.push(entry);
+        Ok(head)
+    }
+    #[inline]
+    pub fn push(&mut self, value: T) -> Output<D> {
+        self.try_push(value).unwrap()
+    }
+
+    /// Verify every link. Fails with `Error::BrokenChain(index)` at the first broken one.
+    pub fn verify(&self) -> Result<()> {
+        let mut prev = Output::<D>::default();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.prev != prev.as_slice() {
+                return Err(Error::BrokenChain(index));
+            }
+            prev = entry.try_hash::<D>()?;
+        }
+        Ok(())
+    }
+    /// Verify every link and that the chain ends at `head`,
+    /// which also catches a changed or dropped last entry.
+    #[inline]
+    pub fn verify_head(&self, head: impl AsBytes) -> Result<()> {
+        self.verify()?;
+        if self.try_head()?.as_slice() != head.as_byte_slice() {
+            return Err(Error::BrokenChain(self.len()));
+        }
+        Ok(())
+    }
+}
+
+impl<T, D: Digest> Default for HashChain<T, D> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Clone, D: Digest> Clone for HashChain<T, D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        HashChain {
+            entries: self.entries.clone(),
+            _digest: PhantomData,
+        }
+    }
+}
+impl<T: fmt::Debug, D: Digest> fmt::Debug for HashChain<T, D> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(&self.entries).finish()
+    }
+}
+impl<T: PartialEq, D: Digest> PartialEq for HashChain<T, D> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+impl<T: Eq, D: Digest> Eq for HashChain<T, D> {}
+
+impl<T: Serialize, D: Digest> Serialize for HashChain<T, D> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+impl<'de, T: Deserialize<'de>, D: Digest> Deserialize<'de> for HashChain<T, D> {
+    #[inline]
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> core::result::Result<Self, De::Error> {
+        Ok(HashChain {
+            entries: Vec::deserialize(deserializer)?,
+            _digest: PhantomData,
+        })
+    }
+}
+
+impl<T: Serialize, D: Digest> ToBytes for HashChain<T, D> {}
+#[cfg(feature = "json")]
+impl<T: Serialize, D: Digest> ToJson for HashChain<T, D> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Test(u32);
+    impl ToBytes for Test {}
+    impl ToHash for Test {}
+
+    #[test]
+    fn test_hash_chain() {
+        let mut chain = HashChain::<Test, Sha256>::new();
+        assert_eq!(chain.head(), Output::<Sha256>::default());
+        assert!(chain.verify().is_ok());
+
+        for i in 0..5 {
+            let head = chain.push(Test(i));
+            assert_eq!(head, chain.head());
+        }
+        assert_eq!(chain.len(), 5);
+        assert_eq!(
+            chain.entries()[1].prev,
+            chain.entries()[0].hash::<Sha256>().to_vec()
+        );
+        assert!(chain.verify_head(chain.head()).is_ok());
+
+        let chain = HashChain::<Test, Sha256>::from_bytes(chain.to_bytes());
+        assert!(chain.verify().is_ok());
+        #[cfg(feature = "json")]
+        assert_eq!(HashChain::<Test, Sha256>::from_json(chain.to_json()), chain);
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let chain = (0..5).fold(HashChain::<Test, Sha256>::new(), |mut chain, i| {
+            chain.push(Test(i));
+            chain
+        });
+        let head = chain.head();
+
+        let mut entries = chain.clone().into_entries();
+        entries[2].value = Test(9);
+        let tampered = HashChain::<Test, Sha256>::from_entries(entries);
+        assert!(matches!(tampered.verify(), Err(Error::BrokenChain(3))));
+
+        let mut entries = chain.clone().into_entries();
+        entries.remove(0);
+        let tampered = HashChain::<Test, Sha256>::from_entries(entries);
+        assert!(matches!(tampered.verify(), Err(Error::BrokenChain(0))));
+
+        let mut entries = chain.into_entries();
+        entries[4].value = Test(9);
+        let tampered = HashChain::<Test, Sha256>::from_entries(entries);
+        assert!(tampered.verify().is_ok());
+        assert!(matches!(
+            tampered.verify_head(head),
+            Err(Error::BrokenChain(5))
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{error::*, io};
+use alloc::vec::Vec;
+
+#[cfg(feature = "bytes")]
+use crate::traits::ToBytes;
+
+#[cfg(any(
+    feature = "bytes",
+    feature = "json",
+    feature = "yaml",
+    feature = "toml",
+    feature = "cbor"
+))]
+use serde::Serialize;
+
+#[cfg(feature = "borsh")]
+use borsh::BorshSerialize;
+
+#[cfg(feature = "json")]
+use serde_json::Value as JsonValue;
+
+/// # A binary encoding of `T`.
+///
+/// Anything generic over a codec (e.g. `ToHash::to_hash_with`) works with every encoding.
+pub trait BinaryCodec<T: ?Sized> {
+    fn try_encode_into(value: &T, writer: impl io::Write) -> Result<()>;
+    #[inline]
+    fn try_encode(value: &T) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        Self::try_encode_into(value, &mut bytes)?;
+        Ok(bytes)
+    }
+    // ---------------------
+    #[inline]
+    fn encode_into(value: &T, writer: impl io::Write) {
+        Self::try_encode_into(value, writer).unwrap()
+    }
+    #[inline]
+    fn encode(value: &T) -> Vec<u8> {
+        Self::try_encode(value).unwrap()
+    }
+}
+
+/// bincode with the `ToBytes::OPTIONS` of `T`. (same as `to_bytes`)
+#[cfg(feature = "bytes")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bincode;
+#[cfg(feature = "bytes")]
+impl<T: ToBytes + Serialize + ?Sized> BinaryCodec<T> for Bincode {
+    #[inline]
+    fn try_encode_into(value: &T, writer: impl io::Write) -> Result<()> {
+        value.try_to_bytes_into(writer)
+    }
+}
+
+/// borsh. (same as `to_borsh_bytes`)
+#[cfg(feature = "borsh")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Borsh;
+#[cfg(feature = "borsh")]
+impl<T: BorshSerialize + ?Sized> BinaryCodec<T> for Borsh {
+    #[inline]
+    fn try_encode_into(value: &T, mut writer: impl io::Write) -> Result<()> {
+        value
+            .serialize(&mut io::borsh_io(&mut writer))
+            .map_err(Error::from)
+    }
+}
+
+/// compact json. (same as `to_json`)
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Json;
+#[cfg(feature = "json")]
+impl<T: Serialize + ?Sized> BinaryCodec<T> for Json {
+    #[inline]
+    fn try_encode_into(value: &T, writer: impl io::Write) -> Result<()> {
+        serde_json::to_writer(writer, value).map_err(Error::Json)
+    }
+}
+
+/// compact json with object keys sorted, independent of field order.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalJson;
+#[cfg(feature = "json")]
+impl<T: Serialize + ?Sized> BinaryCodec<T> for CanonicalJson {
+    #[inline]
+    fn try_encode_into(value: &T, mut writer: impl io::Write) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        write_canonical_json(&value, &mut writer)
+    }
+}
+
+#[cfg(feature = "json")]
+fn write_canonical_json(value: &JsonValue, writer: &mut impl io::Write) -> Result<()> {
+    match value {
+        JsonValue::Array(array) => {
+            writer.write_all(b"[")?;
+            for (i, v) in array.iter().enumerate() {
+                if i != 0 {
+                    writer.write_all(b",")?;
+                }
+                write_canonical_json(v, writer)?;
+            }
+            writer.write_all(b"]")?;
+        }
+        JsonValue::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            writer.write_all(b"{")?;
+            for (i, (k, v)) in entries.into_iter().enumerate() {
+                if i != 0 {
+                    writer.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *writer, k)?;
+                writer.write_all(b":")?;
+                write_canonical_json(v, writer)?;
+            }
+            writer.write_all(b"}")?;
+        }
+        _ => serde_json::to_writer(writer, value)?,
+    }
+    Ok(())
+}
+
+/// yaml. (same as `to_yaml`)
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Yaml;
+#[cfg(feature = "yaml")]
+impl<T: Serialize + ?Sized> BinaryCodec<T> for Yaml {
+    #[inline]
+    fn try_encode_into(value: &T, writer: impl io::Write) -> Result<()> {
+        serde_yaml::to_writer(writer, value).map_err(Error::Yaml)
+    }
+}
+
+/// toml. (same as `to_toml`)
+///
+/// `toml` has no streaming serializer, the document is buffered before writing.
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Toml;
+#[cfg(feature = "toml")]
+impl<T: Serialize + ?Sized> BinaryCodec<T> for Toml {
+    #[inline]
+    fn try_encode_into(value: &T, mut writer: impl io::Write) -> Result<()> {
+        writer
+            .write_all(toml::to_vec(value)?.as_slice())
+            .map_err(Error::Io)
+    }
+}
+
+/// cbor.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cbor;
+#[cfg(feature = "cbor")]
+impl<T: Serialize + ?Sized> BinaryCodec<T> for Cbor {
+    #[inline]
+    fn try_encode_into(value: &T, writer: impl io::Write) -> Result<()> {
+        ciborium::ser::into_writer(value, writer).map_err(Error::Cbor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Test {
+        b: String,
+        a: u32,
+    }
+    impl ToBytes for Test {}
+
+    #[test]
+    fn test_codecs() {
+        let test = Test {
+            b: "hello".to_owned(),
+            a: 1,
+        };
+        assert_eq!(Bincode::encode(&test), test.to_bytes());
+        assert_eq!(Json::encode(&test), br#"{"b":"hello","a":1}"#);
+        assert_eq!(CanonicalJson::encode(&test), br#"{"a":1,"b":"hello"}"#);
+        assert_eq!(Borsh::encode(&1u32), [1, 0, 0, 0]);
+        assert_eq!(Yaml::encode(&test), b"b: hello\na: 1\n");
+        assert_eq!(Toml::encode(&test), b"b = \"hello\"\na = 1\n");
+        #[cfg(feature = "cbor")]
+        assert_eq!(Cbor::encode(&1u32), [1]);
+    }
+}
@@ -0,0 +1,358 @@
+//! # Deterministic directory-tree hashing.
+//!
+//! - files are sorted by their relative path, with `/` as the separator.
+//! - leaf = a `FileEntry` (path, mode, size, content hash) in a `MerkleTree`.
+//! - modes are normalized like git: `100644`, `100755` or `120000` for a symlink.
+//! - empty directories are not hashed.
+
+use crate::{
+    error::*,
+    hash::try_hash_file,
+    merkle::{MerkleOptions, MerkleTree},
+    traits::*,
+};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use digest::{Digest, Output};
+use serde::{Deserialize, Serialize};
+
+const MODE_FILE: u32 = 0o100644;
+const MODE_EXECUTABLE: u32 = 0o100755;
+const MODE_SYMLINK: u32 = 0o120000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirHashOptions {
+    /// Hash the file modes. Turn it off for trees copied across platforms.
+    pub include_mode: bool,
+    /// Hash the targets of symlinks instead of the links.
+    /// A link back to a directory being walked fails with an `io::Error`.
+    pub follow_links: bool,
+}
+impl DirHashOptions {
+    #[inline]
+    pub const fn default() -> Self {
+        DirHashOptions {
+            include_mode: true,
+            follow_links: false,
+        }
+    }
+}
+
+/// # One file of a `DirManifest`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub mode: u32,
+    pub size: u64,
+    /// Lowercase hex of the content hash, or of the link target for a symlink.
+    pub hash: String,
+}
+impl ToBytes for FileEntry {}
+impl ToHash for FileEntry {}
+#[cfg(feature = "json")]
+impl ToJson for FileEntry {}
+
+/// # The per-file hashes of a directory and its root hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirManifest {
+    /// Lowercase hex of the merkle root of `files`.
+    pub root: String,
+    pub files: Vec<FileEntry>,
+}
+impl ToBytes for DirManifest {}
+#[cfg(feature = "json")]
+impl ToJson for DirManifest {}
+
+impl DirManifest {
+    /// Sort `files` by path and compute `root`.
+    #[inline]
+    pub fn try_from_files<D: Digest>(mut files: Vec<FileEntry>) -> Result<Self> {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let root = root_of::<D>(&files)?.to_hex();
+        Ok(DirManifest { root, files })
+    }
+
+    /// Recompute the root from `files` and compare it with `root`.
+    #[inline]
+    pub fn verify<D: Digest>(&self) -> Result<()> {
+        let actual = root_of::<D>(&self.files)?;
+        if actual.to_hex() != self.root.to_lowercase() {
+            return Err(Error::HashMismatch {
+                expected: self.root.clone(),
+                actual: actual.to_hex(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The paths that were added, removed or changed in `other`.
+    pub fn diff(&self, other: &DirManifest) -> DirDiff {
+        let before = self
+            .files
+            .iter()
+            .map(|file| (&file.path, file))
+            .collect::<BTreeMap<_, _>>();
+        let after = other
+            .files
+            .iter()
+            .map(|file| (&file.path, file))
+            .collect::<BTreeMap<_, _>>();
+        let mut diff = DirDiff::default();
+        for (path, file) in &after {
+            match before.get(path) {
+                None => diff.added.push((*path).clone()),
+                Some(old) if old != file => diff.changed.push((*path).clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .map(|path| (*path).clone())
+            .collect();
+        diff
+    }
+}
+
+/// # The difference between two `DirManifest`s, by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+impl DirDiff {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+impl ToBytes for DirDiff {}
+#[cfg(feature = "json")]
+impl ToJson for DirDiff {}
+
+/// Hash every file under `path` into a `DirManifest`.
+#[inline]
+pub fn try_hash_dir<D: Digest>(
+    path: impl AsRef<Path>,
+    options: DirHashOptions,
+) -> Result<DirManifest> {
+    let path = path.as_ref();
+    let mut files = Vec::new();
+    let mut ancestors = vec![fs::canonicalize(path)?];
+    walk::<D>(path, String::new(), options, &mut ancestors, &mut files)?;
+    DirManifest::try_from_files::<D>(files)
+}
+#[inline]
+pub fn hash_dir<D: Digest>(path: impl AsRef<Path>, options: DirHashOptions) -> DirManifest {
+    try_hash_dir::<D>(path, options).unwrap()
+}
+
+fn root_of<D: Digest>(files: &[FileEntry]) -> Result<Output<D>> {
+    let options = MerkleOptions::default();
+    let leaves = files
+        .iter()
+        .map(|file| options.try_leaf_hash::<D, _>(file))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(MerkleTree::<D>::from_leaf_hashes_with(leaves, options).root())
+}
+
+/// `ancestors` are the canonical paths of the directories being walked, to detect cycles.
+fn walk<D: Digest>(
+    dir: &Path,
+    prefix: String,
+    options: DirHashOptions,
+    ancestors: &mut Vec<PathBuf>,
+    files: &mut Vec<FileEntry>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().into_string().map_err(|name| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("non UTF-8 path: {name:?}"),
+            )
+        })?;
+        let path = format!("{prefix}{name}");
+        let full_path = entry.path();
+        let metadata = match options.follow_links {
+            true => fs::metadata(&full_path)?,
+            false => fs::symlink_metadata(&full_path)?,
+        };
+
+        let (mode, size, hash) = if metadata.is_dir() {
+            let canonical = fs::canonicalize(&full_path)?;
+            if ancestors.contains(&canonical) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("symlink cycle: {path} -> {}", canonical.display()),
+                )
+                .into());
+            }
+            ancestors.push(canonical);
+            walk::<D>(&full_path, format!("{path}/"), options, ancestors, files)?;
+            ancestors.pop();
+            continue;
+        } else if metadata.is_symlink() {
+            let target = link_target(&full_path)?;
+            (MODE_SYMLINK, target.len() as u64, D::digest(&target))
+        } else {
+            (
+                file_mode(&metadata),
+                metadata.len(),
+                try_hash_file::<D>(&full_path)?,
+            )
+        };
+        files.push(FileEntry {
+            path,
+            mode: if options.include_mode { mode } else { 0 },
+            size,
+            hash: hash.to_hex(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    match metadata.permissions().mode() & 0o111 {
+        0 => MODE_FILE,
+        _ => MODE_EXECUTABLE,
+    }
+}
+#[cfg(not(unix))]
+fn file_mode(_: &fs::Metadata) -> u32 {
+    MODE_FILE
+}
+
+/// The link target with `/` separators.
+fn link_target(path: &Path) -> Result<Vec<u8>> {
+    let target = fs::read_link(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Ok(target.as_os_str().as_bytes().to_vec())
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(target.to_string_lossy().replace('\\', "/").into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stdto-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/bin")).unwrap();
+        fs::write(dir.join("a.txt"), b"a").unwrap();
+        fs::write(dir.join("src/lib.rs"), b"lib").unwrap();
+        fs::write(dir.join("src/bin/main.rs"), b"main").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_hash_dir() {
+        let dir = temp_tree("hash-dir");
+        let options = DirHashOptions::default();
+        let manifest = hash_dir::<Sha256>(&dir, options);
+
+        let paths = manifest
+            .files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["a.txt", "src/bin/main.rs", "src/lib.rs"]);
+        assert_eq!(manifest.files[0].hash, Sha256::digest(b"a").to_hex());
+        assert_eq!(manifest.files[0].size, 1);
+        assert!(manifest.verify::<Sha256>().is_ok());
+        assert_eq!(hash_dir::<Sha256>(&dir, options), manifest);
+
+        #[cfg(feature = "json")]
+        assert_eq!(DirManifest::from_json(manifest.to_json()), manifest);
+
+        fs::write(dir.join("src/lib.rs"), b"lib2").unwrap();
+        fs::write(dir.join("b.txt"), b"b").unwrap();
+        fs::remove_file(dir.join("a.txt")).unwrap();
+        let changed = hash_dir::<Sha256>(&dir, options);
+        assert_ne!(changed.root, manifest.root);
+
+        let diff = manifest.diff(&changed);
+        assert_eq!(diff.added, ["b.txt"]);
+        assert_eq!(diff.removed, ["a.txt"]);
+        assert_eq!(diff.changed, ["src/lib.rs"]);
+        assert!(manifest.diff(&manifest).is_empty());
+
+        let mut tampered = changed;
+        tampered.files[0].hash = manifest.files[0].hash.clone();
+        assert!(matches!(
+            tampered.verify::<Sha256>(),
+            Err(Error::HashMismatch { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hash_dir_mode() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = temp_tree("hash-dir-mode");
+        let options = DirHashOptions::default();
+        let manifest = hash_dir::<Sha256>(&dir, options);
+
+        fs::set_permissions(dir.join("a.txt"), fs::Permissions::from_mode(0o755)).unwrap();
+        let executable = hash_dir::<Sha256>(&dir, options);
+        assert_eq!(executable.files[0].mode, MODE_EXECUTABLE);
+        assert_eq!(manifest.diff(&executable).changed, ["a.txt"]);
+
+        let without_mode = DirHashOptions {
+            include_mode: false,
+            ..options
+        };
+        let root = hash_dir::<Sha256>(&dir, without_mode).root;
+        fs::set_permissions(dir.join("a.txt"), fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(hash_dir::<Sha256>(&dir, without_mode).root, root);
+
+        symlink("a.txt", dir.join("link")).unwrap();
+        let linked = hash_dir::<Sha256>(&dir, options);
+        let link = linked.files.iter().find(|f| f.path == "link").unwrap();
+        assert_eq!(link.mode, MODE_SYMLINK);
+        assert_eq!(link.hash, Sha256::digest(b"a.txt").to_hex());
+
+        let followed = hash_dir::<Sha256>(
+            &dir,
+            DirHashOptions {
+                follow_links: true,
+                ..options
+            },
+        );
+        let link = followed.files.iter().find(|f| f.path == "link").unwrap();
+        assert_eq!(link.mode, MODE_FILE);
+        assert_eq!(link.hash, Sha256::digest(b"a").to_hex());
+
+        symlink("..", dir.join("src/loop")).unwrap();
+        assert!(try_hash_dir::<Sha256>(&dir, options).is_ok());
+        assert!(matches!(
+            try_hash_dir::<Sha256>(
+                &dir,
+                DirHashOptions {
+                    follow_links: true,
+                    ..options
+                }
+            ),
+            Err(Error::Io(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,9 +1,17 @@
+#[cfg(any(feature = "borsh", feature = "hash"))]
+use alloc::string::String;
+#[cfg(feature = "hash")]
+use alloc::vec::Vec;
+
 #[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[cfg(feature = "bytes")]
     #[error("bytes conversion error: {0}")]
     Bytes(#[from] bincode::Error),
+    #[cfg(feature = "borsh")]
+    #[error("borsh schema error: {0}")]
+    BorshSchema(String),
 
     #[cfg(feature = "json")]
     #[error("json conversion error: {0}")]
@@ -20,18 +28,79 @@ pub enum Error {
     #[error("toml conversion error: {0}")]
     TomlDeserialize(#[from] toml::de::Error),
 
+    #[cfg(feature = "cbor")]
+    #[error("cbor conversion error: {0}")]
+    Cbor(#[from] ciborium::ser::Error<std::io::Error>),
+
+    #[cfg(feature = "hash")]
+    #[error("invalid hash length: expected {0}, got {1}")]
+    HashLength(usize, usize),
+    #[cfg(all(feature = "hash", feature = "bytes"))]
+    #[error("invalid key length")]
+    InvalidKeyLength(#[from] digest::InvalidLength),
+    #[cfg(all(feature = "hash", feature = "bytes"))]
+    #[error("mac mismatch")]
+    MacMismatch,
+    #[cfg(feature = "hash")]
+    #[error("unknown id: {0}")]
+    UnknownId(String),
+    #[cfg(feature = "hash")]
+    #[error("ambiguous id: {prefix} matches {candidates:?}")]
+    AmbiguousId {
+        prefix: String,
+        candidates: Vec<String>,
+    },
+    #[cfg(all(feature = "hash", feature = "hex"))]
+    #[error("hash mismatch: expected {expected}, got {actual}")]
+    HashMismatch { expected: String, actual: String },
+    #[cfg(all(feature = "hash", feature = "bytes"))]
+    #[error("invalid merkle proof")]
+    InvalidProof,
+    #[cfg(all(feature = "hash", feature = "bytes"))]
+    #[error("broken hash chain at entry {0}")]
+    BrokenChain(usize),
+    #[cfg(feature = "multihash")]
+    #[error("invalid multihash")]
+    InvalidMultihash,
+    #[cfg(feature = "multihash")]
+    #[error("multihash code mismatch: expected {0:#x}, got {1:#x}")]
+    MultihashCode(u64, u64),
+    #[cfg(feature = "algorithms")]
+    #[error("unknown hash algorithm: {0}")]
+    UnknownHashAlgorithm(String),
+
+    #[cfg(feature = "signature")]
+    #[error("signature error: {0}")]
+    Signature(signature::Error),
+
     #[error("io error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[from] crate::io::Error),
     #[error("fmt error: {0}")]
-    Fmt(#[from] std::fmt::Error),
+    Fmt(#[from] core::fmt::Error),
     #[error("utf error: {0}")]
-    Utf8(#[from] std::str::Utf8Error),
+    Utf8(#[from] core::str::Utf8Error),
     #[error("parse int error: {0}")]
-    ParseInt(#[from] std::num::ParseIntError),
+    ParseInt(#[from] core::num::ParseIntError),
     #[error("out of bounds error: {0} < {1}")]
     OutOfBounds(usize, usize),
     #[error("odd length")]
     OddLength,
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+#[cfg(all(feature = "borsh", not(feature = "std")))]
+impl From<borsh::maybestd::io::Error> for Error {
+    #[inline]
+    fn from(e: borsh::maybestd::io::Error) -> Self {
+        Error::Io(e.into())
+    }
+}
+
+#[cfg(feature = "signature")]
+impl From<signature::Error> for Error {
+    #[inline]
+    fn from(e: signature::Error) -> Self {
+        Error::Signature(e)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
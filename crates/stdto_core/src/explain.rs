@@ -0,0 +1,602 @@
+use crate::{enums::Endian, error::*};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write as _};
+
+use bincode::Options;
+use serde::{ser, Serialize};
+
+#[cfg(feature = "json")]
+use serde_json::{Map, Value as JsonValue};
+
+/// A labelled byte range of an encoded value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteSpan {
+    pub offset: usize,
+    pub len: usize,
+    /// Field path, e.g. `e.[2].key`. (empty for the root value)
+    pub path: String,
+    /// `u32`, `str`, `[u8]`, `u8 x 32`, ... or `len`, `variant`, `option` for prefixes.
+    ///
+    /// Fixed-length arrays and tuples are the same `serialize_tuple` to serde,
+    /// so their bytes are labelled `u8 x N` and their elements `.0`, `.1`, ...
+    pub ty: String,
+    pub value: Option<String>,
+}
+
+/// # The byte layout of a bincode encoded value.
+///
+/// `Display` renders an annotated hexdump, one span per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytesLayout {
+    pub bytes: Vec<u8>,
+    pub spans: Vec<ByteSpan>,
+}
+
+impl BytesLayout {
+    /// Explain the bytes of `value`, encoded the same way as `ToBytes`.
+    #[inline]
+    pub fn try_new<T: Serialize + ?Sized>(value: &T, endian: Endian) -> Result<Self> {
+        let mut explainer = Explainer {
+            endian,
+            bytes: Vec::new(),
+            spans: Vec::new(),
+            path: Vec::new(),
+        };
+        explainer.value(value).map_err(Error::Bytes)?;
+        Ok(BytesLayout {
+            bytes: explainer.bytes,
+            spans: explainer.spans,
+        })
+    }
+    #[inline]
+    pub fn bytes_of(&self, span: &ByteSpan) -> &[u8] {
+        &self.bytes[span.offset..span.offset + span.len]
+    }
+    #[cfg(feature = "json")]
+    pub fn to_json_value(&self) -> JsonValue {
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                let mut map = Map::new();
+                map.insert("offset".to_owned(), span.offset.into());
+                map.insert("len".to_owned(), span.len.into());
+                map.insert("path".to_owned(), span.path.clone().into());
+                map.insert("type".to_owned(), span.ty.clone().into());
+                map.insert("value".to_owned(), span.value.clone().into());
+                map.insert("hex".to_owned(), hex(self.bytes_of(span)).into());
+                JsonValue::Object(map)
+            })
+            .collect::<Vec<_>>();
+        let mut map = Map::new();
+        map.insert("len".to_owned(), self.bytes.len().into());
+        map.insert("spans".to_owned(), spans.into());
+        map.into()
+    }
+}
+
+const BYTES_PER_LINE: usize = 16;
+
+impl fmt::Display for BytesLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path_width = self.spans.iter().map(|s| s.path.len().max(1)).max();
+        let path_width = path_width.unwrap_or(1);
+        let ty_width = self.spans.iter().map(|s| s.ty.len()).max().unwrap_or(0);
+        for span in &self.spans {
+            let bytes = self.bytes_of(span);
+            for (i, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+                let mut line = format!("{:06x} ", span.offset + i * BYTES_PER_LINE);
+                for b in chunk {
+                    write!(line, " {b:02x}")?;
+                }
+                if i == 0 {
+                    let path = if span.path.is_empty() { "." } else { &span.path };
+                    let width = 7 + BYTES_PER_LINE * 3;
+                    write!(line, "{:<1$}", "", width - line.len())?;
+                    write!(line, "  {path:<path_width$}  {:<ty_width$}", span.ty)?;
+                    if let Some(value) = &span.value {
+                        write!(line, "  {value}")?;
+                    }
+                }
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+// ---------------------
+
+type BincodeResult<T> = core::result::Result<T, bincode::Error>;
+
+struct Explainer {
+    endian: Endian,
+    bytes: Vec<u8>,
+    spans: Vec<ByteSpan>,
+    path: Vec<String>,
+}
+
+impl Explainer {
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        value.serialize(self)
+    }
+
+    fn path(&self) -> String {
+        self.path.join(".")
+    }
+
+    fn encode<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        let options = bincode::options().with_fixint_encoding();
+        match self.endian {
+            Endian::Big => options.with_big_endian().serialize_into(&mut self.bytes, value),
+            Endian::Little => options
+                .with_little_endian()
+                .serialize_into(&mut self.bytes, value),
+            Endian::Native => options
+                .with_native_endian()
+                .serialize_into(&mut self.bytes, value),
+        }
+    }
+
+    fn push_span(&mut self, offset: usize, ty: impl ToString, value: Option<String>) {
+        self.spans.push(ByteSpan {
+            offset,
+            len: self.bytes.len() - offset,
+            path: self.path(),
+            ty: ty.to_string(),
+            value,
+        });
+    }
+
+    fn leaf<T: Serialize + fmt::Debug>(&mut self, ty: &str, value: T) -> BincodeResult<()> {
+        let offset = self.bytes.len();
+        self.encode(&value)?;
+        self.push_span(offset, ty, Some(format!("{value:?}")));
+        Ok(())
+    }
+
+    fn len_prefix(&mut self, len: usize) -> BincodeResult<()> {
+        self.leaf("len", len as u64)
+    }
+
+    fn variant(&mut self, name: &str, variant_index: u32, variant: &str) -> BincodeResult<()> {
+        let offset = self.bytes.len();
+        self.encode(&variant_index)?;
+        self.push_span(offset, "variant", Some(format!("{name}::{variant}")));
+        Ok(())
+    }
+
+    fn raw(&mut self, ty: &str, bytes: &[u8], value: Option<String>) {
+        let offset = self.bytes.len();
+        self.bytes.extend_from_slice(bytes);
+        self.push_span(offset, ty, value);
+    }
+
+    fn compound(&mut self, kind: Coalesce, pushed: bool) -> Compound<'_> {
+        Compound {
+            start: self.spans.len(),
+            explainer: self,
+            index: 0,
+            kind,
+            pushed,
+        }
+    }
+}
+
+/// How `Compound::end` merges the element spans of a `u8` sequence.
+#[derive(Clone, Copy)]
+enum Coalesce {
+    Seq,
+    Tuple,
+    None,
+}
+
+struct Compound<'a> {
+    explainer: &'a mut Explainer,
+    start: usize,
+    index: usize,
+    kind: Coalesce,
+    /// A variant name was pushed onto the path.
+    pushed: bool,
+}
+
+impl Compound<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, segment: String, value: &T) -> BincodeResult<()> {
+        self.explainer.path.push(segment);
+        let result = self.explainer.value(value);
+        self.explainer.path.pop();
+        self.index += 1;
+        result
+    }
+
+    fn end(self) -> BincodeResult<()> {
+        let spans = &mut self.explainer.spans;
+        let elements = &spans[self.start..];
+        let skip = usize::from(matches!(self.kind, Coalesce::Seq));
+        let merge = !matches!(self.kind, Coalesce::None)
+            && elements.len() > skip
+            && elements[skip..].iter().all(|span| span.ty == "u8");
+        if merge {
+            let count = elements.len() - skip;
+            let first = &elements[skip];
+            let span = ByteSpan {
+                offset: first.offset,
+                len: count,
+                path: self.explainer.path.join("."),
+                ty: match self.kind {
+                    Coalesce::Tuple => format!("u8 x {count}"),
+                    _ => "[u8]".to_owned(),
+                },
+                value: None,
+            };
+            spans.truncate(self.start + skip);
+            spans.push(span);
+        }
+        if self.pushed {
+            self.explainer.path.pop();
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Explainer {
+    type Ok = ();
+    type Error = bincode::Error;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> BincodeResult<()> {
+        self.leaf("bool", v)
+    }
+    fn serialize_i8(self, v: i8) -> BincodeResult<()> {
+        self.leaf("i8", v)
+    }
+    fn serialize_i16(self, v: i16) -> BincodeResult<()> {
+        self.leaf("i16", v)
+    }
+    fn serialize_i32(self, v: i32) -> BincodeResult<()> {
+        self.leaf("i32", v)
+    }
+    fn serialize_i64(self, v: i64) -> BincodeResult<()> {
+        self.leaf("i64", v)
+    }
+    fn serialize_i128(self, v: i128) -> BincodeResult<()> {
+        self.leaf("i128", v)
+    }
+    fn serialize_u8(self, v: u8) -> BincodeResult<()> {
+        self.leaf("u8", v)
+    }
+    fn serialize_u16(self, v: u16) -> BincodeResult<()> {
+        self.leaf("u16", v)
+    }
+    fn serialize_u32(self, v: u32) -> BincodeResult<()> {
+        self.leaf("u32", v)
+    }
+    fn serialize_u64(self, v: u64) -> BincodeResult<()> {
+        self.leaf("u64", v)
+    }
+    fn serialize_u128(self, v: u128) -> BincodeResult<()> {
+        self.leaf("u128", v)
+    }
+    fn serialize_f32(self, v: f32) -> BincodeResult<()> {
+        self.leaf("f32", v)
+    }
+    fn serialize_f64(self, v: f64) -> BincodeResult<()> {
+        self.leaf("f64", v)
+    }
+    fn serialize_char(self, v: char) -> BincodeResult<()> {
+        self.leaf("char", v)
+    }
+    fn serialize_str(self, v: &str) -> BincodeResult<()> {
+        self.len_prefix(v.len())?;
+        self.raw("str", v.as_bytes(), Some(format!("{v:?}")));
+        Ok(())
+    }
+    fn serialize_bytes(self, v: &[u8]) -> BincodeResult<()> {
+        self.len_prefix(v.len())?;
+        self.raw("[u8]", v, None);
+        Ok(())
+    }
+    fn serialize_none(self) -> BincodeResult<()> {
+        let offset = self.bytes.len();
+        self.encode(&0u8)?;
+        self.push_span(offset, "option", Some("None".to_owned()));
+        Ok(())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> BincodeResult<()> {
+        let offset = self.bytes.len();
+        self.encode(&1u8)?;
+        self.push_span(offset, "option", Some("Some".to_owned()));
+        self.value(value)
+    }
+    fn serialize_unit(self) -> BincodeResult<()> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> BincodeResult<()> {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> BincodeResult<()> {
+        self.variant(name, variant_index, variant)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> BincodeResult<()> {
+        self.value(value)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> BincodeResult<()> {
+        self.variant(name, variant_index, variant)?;
+        self.path.push(variant.to_owned());
+        let result = self.value(value);
+        self.path.pop();
+        result
+    }
+    fn serialize_seq(self, len: Option<usize>) -> BincodeResult<Compound<'a>> {
+        let len = len.ok_or_else(|| Box::new(bincode::ErrorKind::SequenceMustHaveLength))?;
+        let start = self.spans.len();
+        self.len_prefix(len)?;
+        let mut compound = self.compound(Coalesce::Seq, false);
+        compound.start = start;
+        Ok(compound)
+    }
+    fn serialize_tuple(self, _len: usize) -> BincodeResult<Compound<'a>> {
+        Ok(self.compound(Coalesce::Tuple, false))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> BincodeResult<Compound<'a>> {
+        Ok(self.compound(Coalesce::None, false))
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> BincodeResult<Compound<'a>> {
+        self.variant(name, variant_index, variant)?;
+        self.path.push(variant.to_owned());
+        Ok(self.compound(Coalesce::None, true))
+    }
+    fn serialize_map(self, len: Option<usize>) -> BincodeResult<Compound<'a>> {
+        let len = len.ok_or_else(|| Box::new(bincode::ErrorKind::SequenceMustHaveLength))?;
+        self.len_prefix(len)?;
+        Ok(self.compound(Coalesce::None, false))
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> BincodeResult<Compound<'a>> {
+        Ok(self.compound(Coalesce::None, false))
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> BincodeResult<Compound<'a>> {
+        self.variant(name, variant_index, variant)?;
+        self.path.push(variant.to_owned());
+        Ok(self.compound(Coalesce::None, true))
+    }
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        self.element(format!("[{}]", self.index), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        self.element(self.index.to_string(), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        self.element(self.index.to_string(), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        self.element(self.index.to_string(), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> BincodeResult<()> {
+        self.explainer.path.push(format!("[{}]", self.index));
+        self.explainer.path.push("key".to_owned());
+        let result = self.explainer.value(key);
+        self.explainer.path.pop();
+        result
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> BincodeResult<()> {
+        self.explainer.path.push("value".to_owned());
+        let result = self.explainer.value(value);
+        self.explainer.path.truncate(self.explainer.path.len() - 2);
+        self.index += 1;
+        result
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> BincodeResult<()> {
+        self.element(key.to_owned(), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = bincode::Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> BincodeResult<()> {
+        self.element(key.to_owned(), value)
+    }
+    fn end(self) -> BincodeResult<()> {
+        Compound::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Test {
+        a: u32,
+        b: String,
+        c: [u8; 4],
+        d: Option<Kind>,
+        e: BTreeMap<u8, f64>,
+        f: (u8, u16),
+        g: Box<(u8, u8)>,
+    }
+
+    #[derive(Serialize)]
+    enum Kind {
+        Pair(i8, bool),
+    }
+
+    fn test() -> Test {
+        Test {
+            a: 1,
+            b: "test".to_owned(),
+            c: [5; 4],
+            d: Some(Kind::Pair(-1, true)),
+            e: BTreeMap::from([(7, 0.5)]),
+            f: (1, 2),
+            g: Box::new((3, 4)),
+        }
+    }
+
+    #[test]
+    fn test_explain_bytes() {
+        let test = test();
+        let layout = BytesLayout::try_new(&test, Endian::Little).unwrap();
+        let bytes = bincode::options()
+            .with_fixint_encoding()
+            .serialize(&test)
+            .unwrap();
+        assert_eq!(layout.bytes, bytes);
+
+        let spans = layout
+            .spans
+            .iter()
+            .map(|s| (s.offset, s.len, s.path.as_str(), s.ty.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            [
+                (0, 4, "a", "u32"),
+                (4, 8, "b", "len"),
+                (12, 4, "b", "str"),
+                (16, 4, "c", "u8 x 4"),
+                (20, 1, "d", "option"),
+                (21, 4, "d", "variant"),
+                (25, 1, "d.Pair.0", "i8"),
+                (26, 1, "d.Pair.1", "bool"),
+                (27, 8, "e", "len"),
+                (35, 1, "e.[0].key", "u8"),
+                (36, 8, "e.[0].value", "f64"),
+                (44, 1, "f.0", "u8"),
+                (45, 2, "f.1", "u16"),
+                (47, 2, "g", "u8 x 2"),
+            ]
+        );
+        assert_eq!(layout.spans[5].value.as_deref(), Some("Kind::Pair"));
+
+        let text = layout.to_string();
+        assert_eq!(text.lines().count(), spans.len());
+        assert!(text.starts_with("000000  01 00 00 00"));
+    }
+
+    #[test]
+    fn test_explain_bytes_big_endian() {
+        let layout = BytesLayout::try_new(&vec![1u8, 2, 3], Endian::Big).unwrap();
+        assert_eq!(layout.bytes, [0, 0, 0, 0, 0, 0, 0, 3, 1, 2, 3]);
+        assert_eq!(layout.spans.len(), 2);
+        assert_eq!(layout.spans[1].ty, "[u8]");
+        #[cfg(feature = "json")]
+        assert_eq!(
+            layout.to_json_value()["spans"][0],
+            serde_json::json!({
+                "offset": 0, "len": 8, "path": "", "type": "len", "value": "3", "hex": "0000000000000003"
+            })
+        );
+    }
+}
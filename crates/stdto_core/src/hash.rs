@@ -0,0 +1,625 @@
+#[cfg(feature = "hex")]
+use crate::{enums::HexMode, traits::ToHex};
+use crate::{error::*, io};
+#[cfg(any(feature = "bytes", feature = "hex"))]
+use alloc::vec::Vec;
+use core::{cmp, fmt, hash, marker::PhantomData};
+
+use digest::{Digest, Output};
+
+#[cfg(all(feature = "serde", feature = "hex"))]
+use serde::de;
+#[cfg(any(feature = "bytes", all(feature = "serde", feature = "hex")))]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "bytes")]
+use crate::traits::{ToBytes, ToHash};
+#[cfg(feature = "bytes")]
+use core::ops::{Deref, DerefMut};
+#[cfg(feature = "bytes")]
+use std::sync::OnceLock;
+
+/// # A hash of `T` computed with `D`.
+///
+/// `Display` is lowercase hex (`{:#}` adds `0x`), `{:X}` is uppercase.
+/// serde uses a hex string in human-readable formats and raw bytes in binary ones.
+pub struct Hash<D: Digest, T: ?Sized> {
+    output: Output<D>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<D: Digest, T: ?Sized> Hash<D, T> {
+    #[inline]
+    pub fn new(output: Output<D>) -> Self {
+        Hash {
+            output,
+            _marker: PhantomData,
+        }
+    }
+    #[inline]
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self> {
+        let len = <D as Digest>::output_size();
+        if bytes.len() != len {
+            return Err(Error::HashLength(len, bytes.len()));
+        }
+        Ok(Self::new(Output::<D>::clone_from_slice(bytes)))
+    }
+    #[inline]
+    pub fn as_output(&self) -> &Output<D> {
+        &self.output
+    }
+    #[inline]
+    pub fn into_output(self) -> Output<D> {
+        self.output
+    }
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.output
+    }
+}
+
+impl<D: Digest, T: ?Sized> From<Output<D>> for Hash<D, T> {
+    #[inline]
+    fn from(output: Output<D>) -> Self {
+        Self::new(output)
+    }
+}
+
+impl<D: Digest, T: ?Sized> AsRef<[u8]> for Hash<D, T> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.output
+    }
+}
+
+impl<D: Digest, T: ?Sized> Clone for Hash<D, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.output.clone())
+    }
+}
+impl<D: Digest, T: ?Sized> Copy for Hash<D, T> where Output<D>: Copy {}
+
+impl<D: Digest, T: ?Sized> PartialEq for Hash<D, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.output == other.output
+    }
+}
+impl<D: Digest, T: ?Sized> Eq for Hash<D, T> {}
+
+impl<D: Digest, T: ?Sized> PartialOrd for Hash<D, T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<D: Digest, T: ?Sized> Ord for Hash<D, T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<D: Digest, T: ?Sized> hash::Hash for Hash<D, T> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+// --- hex ---
+
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> Hash<D, T> {
+    #[inline]
+    fn fmt_with_mode(&self, f: &mut fmt::Formatter, upper: bool) -> fmt::Result {
+        let mode = match (upper, f.alternate()) {
+            (false, false) => HexMode::Lower,
+            (false, true) => HexMode::Lower0x,
+            (true, false) => HexMode::Upper,
+            (true, true) => HexMode::Upper0x,
+        };
+        self.try_to_hex_into_with_mode(f, mode)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> fmt::Display for Hash<D, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_mode(f, false)
+    }
+}
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> fmt::LowerHex for Hash<D, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_mode(f, false)
+    }
+}
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> fmt::UpperHex for Hash<D, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_mode(f, true)
+    }
+}
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> fmt::Debug for Hash<D, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash({:#x})", self)
+    }
+}
+#[cfg(not(feature = "hex"))]
+impl<D: Digest, T: ?Sized> fmt::Debug for Hash<D, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash({:?})", self.as_slice())
+    }
+}
+
+/// Parse hex, with or without `0x`, in either case.
+#[cfg(feature = "hex")]
+impl<D: Digest, T: ?Sized> core::str::FromStr for Hash<D, T> {
+    type Err = Error;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.strip_prefix("0X").unwrap_or(s);
+        Self::try_from_slice(&Vec::<u8>::try_from_hex(s)?)
+    }
+}
+
+// --- serde ---
+
+#[cfg(all(feature = "serde", feature = "hex"))]
+impl<D: Digest, T: ?Sized> Serialize for Hash<D, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(self.as_slice())
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "hex"))]
+impl<'de, D: Digest, T: ?Sized> Deserialize<'de> for Hash<D, T> {
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> core::result::Result<Self, De::Error> {
+        struct Visitor<D: Digest, T: ?Sized>(PhantomData<fn() -> Hash<D, T>>);
+        impl<'de, D: Digest, T: ?Sized> de::Visitor<'de> for Visitor<D, T> {
+            type Value = Hash<D, T>;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a {} byte hash", <D as Digest>::output_size())
+            }
+            fn visit_str<E: de::Error>(self, v: &str) -> core::result::Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> core::result::Result<Self::Value, E> {
+                Hash::try_from_slice(v).map_err(E::custom)
+            }
+            fn visit_seq<A: de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> core::result::Result<Self::Value, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Hash::try_from_slice(&bytes).map_err(de::Error::custom)
+            }
+        }
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Visitor(PhantomData))
+        } else {
+            deserializer.deserialize_bytes(Visitor(PhantomData))
+        }
+    }
+}
+
+// --- Hashed ---
+
+/// # `T` with its `to_hash` computed once.
+///
+/// Derefs to `T`. Mutation goes through `get_mut`, which drops the cached hash.
+/// serde is transparent, it de/serializes as `T`.
+#[cfg(feature = "bytes")]
+pub struct Hashed<T, D: Digest> {
+    value: T,
+    hash: OnceLock<Output<D>>,
+}
+
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> Hashed<T, D>
+where
+    T: ToHash + ToBytes + Serialize,
+{
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Hashed {
+            value,
+            hash: OnceLock::new(),
+        }
+    }
+    /// The cached hash, computed on first use.
+    #[inline]
+    pub fn try_hash(&self) -> Result<Hash<D, T>> {
+        if let Some(hash) = self.hash.get() {
+            return Ok(Hash::new(hash.clone()));
+        }
+        let hash = self.value.try_to_hash::<D>()?;
+        let _ = self.hash.set(hash.clone());
+        Ok(Hash::new(hash))
+    }
+    #[inline]
+    pub fn hash(&self) -> Hash<D, T> {
+        self.try_hash().unwrap()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> Hashed<T, D> {
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+    /// Mutable access. The cached hash is invalidated before the guard is handed out.
+    #[inline]
+    pub fn get_mut(&mut self) -> HashedMut<'_, T, D> {
+        self.hash.take();
+        HashedMut(self)
+    }
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// A mutable guard of `Hashed`. The hash is recomputed on the next `hash` after it.
+#[cfg(feature = "bytes")]
+pub struct HashedMut<'a, T, D: Digest>(&'a mut Hashed<T, D>);
+
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> Deref for HashedMut<'_, T, D> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0.value
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> DerefMut for HashedMut<'_, T, D> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0.value
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> Deref for Hashed<T, D> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T, D: Digest> AsRef<T> for Hashed<T, D> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T: ToHash + ToBytes + Serialize, D: Digest> From<T> for Hashed<T, D> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T: Clone, D: Digest> Clone for Hashed<T, D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Hashed {
+            value: self.value.clone(),
+            hash: self.hash.clone(),
+        }
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T: fmt::Debug, D: Digest> fmt::Debug for Hashed<T, D> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T: PartialEq, D: Digest> PartialEq for Hashed<T, D> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+#[cfg(feature = "bytes")]
+impl<T: Eq, D: Digest> Eq for Hashed<T, D> {}
+
+#[cfg(feature = "bytes")]
+impl<T: Serialize, D: Digest> Serialize for Hashed<T, D> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+#[cfg(feature = "bytes")]
+impl<'de, T, D: Digest> Deserialize<'de> for Hashed<T, D>
+where
+    T: ToHash + ToBytes + Serialize + Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> core::result::Result<Self, De::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+// --- collections ---
+
+/// Hash an ordered sequence of items, owned or borrowed.
+///
+/// `D(0x00 || h(item_0) || h(item_1) || .. || len as u64 le)` with `h = to_hash::<D>`.
+#[cfg(feature = "bytes")]
+#[inline]
+pub fn try_hash_seq<D: Digest>(
+    items: impl IntoIterator<Item = impl ToHash + ToBytes + Serialize>,
+) -> Result<Output<D>> {
+    let mut hasher = D::new().chain_update([0x00]);
+    let mut len = 0u64;
+    for item in items {
+        hasher.update(item.try_to_hash::<D>()?);
+        len += 1;
+    }
+    hasher.update(len.to_le_bytes());
+    Ok(hasher.finalize())
+}
+#[cfg(feature = "bytes")]
+#[inline]
+pub fn hash_seq<D: Digest>(
+    items: impl IntoIterator<Item = impl ToHash + ToBytes + Serialize>,
+) -> Output<D> {
+    try_hash_seq::<D>(items).unwrap()
+}
+
+/// Hash an unordered collection of items, independent of the iteration order.
+///
+/// The item hashes are sorted: `D(0x01 || sorted h(item).. || len as u64 le)`.
+/// Duplicates are kept, so it is a multiset hash.
+#[cfg(feature = "bytes")]
+#[inline]
+pub fn try_hash_set<D: Digest>(
+    items: impl IntoIterator<Item = impl ToHash + ToBytes + Serialize>,
+) -> Result<Output<D>> {
+    let mut hashes = items
+        .into_iter()
+        .map(|item| item.try_to_hash::<D>())
+        .collect::<Result<Vec<_>>>()?;
+    hashes.sort_unstable();
+    let mut hasher = D::new().chain_update([0x01]);
+    for hash in &hashes {
+        hasher.update(hash);
+    }
+    hasher.update((hashes.len() as u64).to_le_bytes());
+    Ok(hasher.finalize())
+}
+#[cfg(feature = "bytes")]
+#[inline]
+pub fn hash_set<D: Digest>(
+    items: impl IntoIterator<Item = impl ToHash + ToBytes + Serialize>,
+) -> Output<D> {
+    try_hash_set::<D>(items).unwrap()
+}
+
+// --- readers ---
+
+/// Hash everything read from `reader`, streamed in chunks.
+#[inline]
+pub fn try_hash_reader<D: Digest>(mut reader: impl io::Read) -> Result<Output<D>> {
+    let mut hasher = D::new();
+    let mut buf = [0u8; 8 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(hasher.finalize()),
+            Ok(n) => hasher.update(&buf[..n]),
+            // retried, like `std::io::copy`
+            #[cfg(feature = "std")]
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+#[inline]
+pub fn hash_reader<D: Digest>(reader: impl io::Read) -> Output<D> {
+    try_hash_reader::<D>(reader).unwrap()
+}
+
+/// Hash the contents of a file, streamed in chunks. (same as `sha256sum`, ...)
+#[cfg(feature = "std")]
+#[inline]
+pub fn try_hash_file<D: Digest>(path: impl AsRef<std::path::Path>) -> Result<Output<D>> {
+    try_hash_reader::<D>(std::fs::File::open(path)?)
+}
+#[cfg(feature = "std")]
+#[inline]
+pub fn hash_file<D: Digest>(path: impl AsRef<std::path::Path>) -> Output<D> {
+    try_hash_file::<D>(path).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::*;
+    use sha2::Sha256;
+    use std::collections::BTreeMap;
+
+    #[derive(serde::Serialize)]
+    struct Test {
+        a: u32,
+    }
+    impl ToBytes for Test {}
+    impl ToHash for Test {}
+    impl ToJson for Test {}
+
+    #[test]
+    fn test_typed_hash() {
+        let hash = Test { a: 1 }.typed_hash::<Sha256>();
+        assert_eq!(hash.into_output(), Test { a: 1 }.to_hash::<Sha256>());
+
+        let hex = format!("{hash}");
+        assert_eq!(hex, hash.to_hex());
+        assert_eq!(format!("{hash:#}"), hash.to_hex_with_0x());
+        assert_eq!(format!("{hash:X}"), hash.to_upper_hex());
+        assert_eq!(
+            format!("{hash:#X}"),
+            hash.try_to_hex_with_mode(HexMode::Upper0x).unwrap()
+        );
+
+        assert_eq!(hex.parse::<Hash<Sha256, Test>>().unwrap(), hash);
+        assert_eq!(
+            hash.to_upper_hex_with_0x()
+                .parse::<Hash<Sha256, Test>>()
+                .unwrap(),
+            hash
+        );
+        assert!("00".parse::<Hash<Sha256, Test>>().is_err());
+
+        let map = BTreeMap::from([(hash, 1)]);
+        assert_eq!(map[&hash], 1);
+    }
+
+    #[test]
+    fn test_typed_hash_serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Block {
+            parent: Hash<Sha256, Block>,
+        }
+        impl ToBytes for Block {}
+        impl ToJson for Block {}
+
+        let parent = Test { a: 1 }.typed_hash::<Sha256>().into_output().into();
+        let block = Block { parent };
+        let json = block.to_json();
+        assert_eq!(json, format!(r#"{{"parent":"{parent}"}}"#));
+        assert_eq!(Block::from_json(json), block);
+
+        let bytes = block.to_bytes();
+        assert_eq!(bytes.len(), 8 + 32);
+        assert_eq!(Block::from_bytes(bytes), block);
+    }
+
+    #[test]
+    fn test_hashed() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Tx {
+            a: u32,
+        }
+        impl ToBytes for Tx {}
+        impl ToHash for Tx {}
+        impl ToJson for Tx {}
+
+        let mut tx = Hashed::<Tx, Sha256>::new(Tx { a: 1 });
+        assert_eq!(tx.a, 1);
+        assert_eq!(tx.hash(), Tx { a: 1 }.typed_hash());
+        assert!(tx.hash.get().is_some());
+
+        tx.get_mut().a = 2;
+        assert!(tx.hash.get().is_none());
+        assert_eq!(tx.hash(), Tx { a: 2 }.typed_hash());
+
+        // a forgotten guard must not leave the old hash behind
+        let mut guard = core::mem::ManuallyDrop::new(tx.get_mut());
+        guard.a = 3;
+        assert_eq!(tx.hash(), Tx { a: 3 }.typed_hash());
+        tx.get_mut().a = 2;
+
+        let json = serde_json::to_string(&tx).unwrap();
+        assert_eq!(json, r#"{"a":2}"#);
+        let tx = serde_json::from_str::<Hashed<Tx, Sha256>>(&json).unwrap();
+        assert_eq!(tx.into_inner(), Tx { a: 2 });
+    }
+
+    #[test]
+    fn test_hash_seq_and_set() {
+        use std::collections::{BTreeSet, HashSet};
+
+        #[derive(serde::Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        struct Item(u32);
+        impl ToBytes for Item {}
+        impl ToHash for Item {}
+
+        let items = (0..10).map(Item).collect::<Vec<_>>();
+        let seq = hash_seq::<Sha256>(&items);
+        assert_eq!(seq, hash_seq::<Sha256>(items.iter()));
+        assert_ne!(seq, hash_seq::<Sha256>(items.iter().rev()));
+        assert_ne!(seq, hash_seq::<Sha256>(&items[1..]));
+        assert_eq!(seq, hash_seq::<Sha256>((0..10).map(Item)));
+        assert_ne!(
+            hash_set::<Sha256>(&items),
+            hash_set::<Sha256>(items.iter().chain([&Item(0)]))
+        );
+
+        let set = hash_set::<Sha256>(&items);
+        assert_ne!(set, seq);
+        assert_eq!(set, hash_set::<Sha256>(items.iter().rev()));
+        assert_eq!(
+            set,
+            hash_set::<Sha256>(items.iter().collect::<HashSet<_>>())
+        );
+        assert_eq!(
+            set,
+            hash_set::<Sha256>(&items.iter().collect::<BTreeSet<_>>())
+        );
+        assert_eq!(set, hash_set::<Sha256>(items.into_iter().rev()));
+
+        let empty: [Item; 0] = [];
+        assert_ne!(hash_seq::<Sha256>(&empty), hash_set::<Sha256>(&empty));
+    }
+
+    #[test]
+    fn test_hash_raw_bytes() {
+        use crate::traits::AsBytes;
+
+        let want = Sha256::digest(b"hello world");
+        assert_eq!("hello world".digest::<Sha256>(), want);
+        assert_eq!(b"hello world".to_vec().digest::<Sha256>(), want);
+
+        let bytes = (0..100_000u32)
+            .flat_map(u32::to_le_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(hash_reader::<Sha256>(&bytes[..]), bytes.digest::<Sha256>());
+
+        // a signal interrupting a read is not an error
+        struct Interrupting<'a>(&'a [u8], bool);
+        impl std::io::Read for Interrupting<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.1 = !self.1;
+                if self.1 {
+                    return Err(std::io::ErrorKind::Interrupted.into());
+                }
+                self.0.read(buf)
+            }
+        }
+        let reader = Interrupting(&bytes, false);
+        assert_eq!(hash_reader::<Sha256>(reader), bytes.digest::<Sha256>());
+
+        let path = std::env::temp_dir().join(format!("stdto-hash-file-{}", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(hash_file::<Sha256>(&path), bytes.digest::<Sha256>());
+        std::fs::remove_file(&path).unwrap();
+        assert!(try_hash_file::<Sha256>(&path).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! # Content-derived identifiers.
+//!
+//! - `short_hex` / `short_base32`: a git-style prefix of a hash.
+//! - `uuid_v8` / `uuid_v5`: a UUID from a hash. (the feature `uuid`)
+//! - `ShortIdIndex`: resolves abbreviated ids, like git resolves short SHAs.
+
+use crate::{error::*, traits::AsBytes};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+/// RFC 4648, lowercase.
+const BASE32: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Lowercase hex of `hash`, cut to `len` digits.
+#[inline]
+pub fn short_hex(hash: impl AsBytes, len: usize) -> String {
+    hash.as_byte_slice()
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .take(len)
+        .map(|digit| HEX[digit as usize] as char)
+        .collect()
+}
+
+/// Lowercase base32 (RFC 4648, no padding) of `hash`, cut to `len` digits.
+pub fn short_base32(hash: impl AsBytes, len: usize) -> String {
+    let bytes = hash.as_byte_slice();
+    let mut id = String::with_capacity((bytes.len() * 8).div_ceil(5).min(len));
+    let (mut buffer, mut bits) = (0u16, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 && id.len() < len {
+            bits -= 5;
+            id.push(BASE32[(buffer >> bits) as usize & 0x1f] as char);
+        }
+    }
+    if bits > 0 && id.len() < len {
+        id.push(BASE32[(buffer << (5 - bits)) as usize & 0x1f] as char);
+    }
+    id
+}
+
+/// UUIDv8 from the first 16 bytes of `hash`, with the version and variant bits set.
+#[cfg(feature = "uuid")]
+#[inline]
+pub fn uuid_v8(hash: impl AsBytes) -> Result<Uuid> {
+    let hash = hash.as_byte_slice();
+    let bytes = hash
+        .get(..16)
+        .ok_or(Error::HashLength(16, hash.len()))?
+        .try_into()
+        .unwrap();
+    Ok(Uuid::new_v8(bytes))
+}
+/// UUIDv5 of `hash` in `namespace`.
+#[cfg(feature = "uuid")]
+#[inline]
+pub fn uuid_v5(namespace: &Uuid, hash: impl AsBytes) -> Uuid {
+    Uuid::new_v5(namespace, hash.as_byte_slice())
+}
+
+/// # An index of full ids that resolves unique prefixes.
+///
+/// Ids and prefixes are compared in lowercase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortIdIndex<V = ()> {
+    ids: BTreeMap<String, V>,
+}
+
+impl<V> ShortIdIndex<V> {
+    #[inline]
+    pub fn new() -> Self {
+        ShortIdIndex {
+            ids: BTreeMap::new(),
+        }
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+    #[inline]
+    pub fn insert(&mut self, id: impl ToString, value: V) -> Option<V> {
+        self.ids.insert(id.to_string().to_lowercase(), value)
+    }
+    #[inline]
+    pub fn remove(&mut self, id: &str) -> Option<V> {
+        self.ids.remove(&id.to_lowercase())
+    }
+    /// Get by the full id.
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&V> {
+        self.ids.get(&id.to_lowercase())
+    }
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.ids.iter().map(|(id, value)| (id.as_str(), value))
+    }
+
+    /// Resolve a prefix to the one id it starts, or the id equal to it.
+    ///
+    /// Fails with `Error::UnknownId` or `Error::AmbiguousId` with every candidate.
+    pub fn resolve(&self, prefix: &str) -> Result<(&str, &V)> {
+        let prefix = prefix.to_lowercase();
+        let mut candidates = self
+            .ids
+            .range(prefix.clone()..)
+            .take_while(|(id, _)| id.starts_with(&prefix));
+        match (candidates.next(), candidates.next()) {
+            (None, _) => Err(Error::UnknownId(prefix)),
+            (Some((id, value)), None) => Ok((id, value)),
+            (Some((id, value)), Some(_)) if *id == prefix => Ok((id, value)),
+            (Some(_), Some(_)) => Err(Error::AmbiguousId {
+                candidates: self
+                    .ids
+                    .range(prefix.clone()..)
+                    .take_while(|(id, _)| id.starts_with(&prefix))
+                    .map(|(id, _)| id.clone())
+                    .collect::<Vec<_>>(),
+                prefix,
+            }),
+        }
+    }
+
+    /// The shortest prefix of `id`, at least `min_len` long, that no other id starts with.
+    pub fn abbreviate<'a>(&self, id: &'a str, min_len: usize) -> &'a str {
+        let lower = id.to_lowercase();
+        let common = |other: &String| {
+            other
+                .bytes()
+                .zip(lower.bytes())
+                .take_while(|(a, b)| a == b)
+                .count()
+        };
+        let before = self.ids.range(..lower.clone()).next_back();
+        let after = self
+            .ids
+            .range(lower.clone()..)
+            .find(|(other, _)| **other != lower);
+        let shared = before
+            .into_iter()
+            .chain(after)
+            .map(|(other, _)| common(other))
+            .max()
+            .unwrap_or(0);
+        let len = (shared + 1).max(min_len).min(id.len());
+        id.get(..len).unwrap_or(id)
+    }
+}
+
+impl<V> Default for ShortIdIndex<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: ToString, V> FromIterator<(K, V)> for ShortIdIndex<V> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut index = Self::new();
+        index.extend(iter);
+        index
+    }
+}
+impl<K: ToString, V> Extend<(K, V)> for ShortIdIndex<V> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (id, value) in iter {
+            self.insert(id, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_id() {
+        let hash = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        assert_eq!(short_hex(hash, 7), "deadbee");
+        assert_eq!(short_hex(hash, 100), "deadbeef01");
+        // RFC 4648 test vectors
+        assert_eq!(short_base32(b"f", 100), "my");
+        assert_eq!(short_base32(b"foobar", 100), "mzxw6ytboi");
+        assert_eq!(short_base32(b"foobar", 4), "mzxw");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid() {
+        let hash = [0xab; 32];
+        let uuid = uuid_v8(hash).unwrap();
+        assert_eq!(uuid.get_version_num(), 8);
+        assert_eq!(uuid.as_bytes()[..6], [0xab; 6]);
+        assert!(uuid_v8([0xab; 8]).is_err());
+
+        let uuid = uuid_v5(&Uuid::NAMESPACE_OID, hash);
+        assert_eq!(uuid.get_version_num(), 5);
+        assert_eq!(uuid, uuid_v5(&Uuid::NAMESPACE_OID, hash));
+    }
+
+    #[test]
+    fn test_short_id_index() {
+        let index = ["deadbeef", "deadf00d", "c0ffee00", "C0FFEE11"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, i))
+            .collect::<ShortIdIndex<_>>();
+
+        assert_eq!(index.resolve("c0ffee1").unwrap(), ("c0ffee11", &3));
+        assert_eq!(index.resolve("DEADB").unwrap(), ("deadbeef", &0));
+        assert!(matches!(index.resolve("1234"), Err(Error::UnknownId(_))));
+        match index.resolve("dead") {
+            Err(Error::AmbiguousId { prefix, candidates }) => {
+                assert_eq!(prefix, "dead");
+                assert_eq!(candidates, ["deadbeef", "deadf00d"]);
+            }
+            _ => panic!("expected an ambiguous id"),
+        }
+
+        assert_eq!(index.abbreviate("deadbeef", 2), "deadb");
+        assert_eq!(index.abbreviate("c0ffee00", 2), "c0ffee0");
+        assert_eq!(index.abbreviate("c0ffee00", 8), "c0ffee00");
+        assert_eq!(index.abbreviate("12345678", 2), "12");
+    }
+}
@@ -0,0 +1,354 @@
+//! `std::io` with the `std` feature, otherwise a minimal `Read`/`Write` on `core` + `alloc`.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::*;
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    #[non_exhaustive]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        InvalidInput,
+        InvalidData,
+        WriteZero,
+        UnexpectedEof,
+        Other,
+    }
+
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+    }
+    impl Error {
+        #[inline]
+        pub const fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+    impl From<ErrorKind> for Error {
+        #[inline]
+        fn from(kind: ErrorKind) -> Self {
+            Error { kind }
+        }
+    }
+    impl fmt::Display for Error {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self.kind)
+        }
+    }
+    impl core::error::Error for Error {}
+
+    type Result<T> = core::result::Result<T, Error>;
+
+    /// A minimal `std::io::Write`.
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+        #[inline]
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(ErrorKind::WriteZero.into()),
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+    impl<W: Write + ?Sized> Write for &mut W {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+    impl Write for Vec<u8> {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    /// A minimal `std::io::Read`.
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+        #[inline]
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+        #[inline]
+        fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+            let start = buf.len();
+            let mut chunk = [0u8; 256];
+            loop {
+                match self.read(&mut chunk)? {
+                    0 => return Ok(buf.len() - start),
+                    n => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+        }
+    }
+    impl<R: Read + ?Sized> Read for &mut R {
+        #[inline]
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+    impl Read for &[u8] {
+        #[inline]
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = buf.len().min(self.len());
+            let (head, tail) = self.split_at(n);
+            buf[..n].copy_from_slice(head);
+            *self = tail;
+            Ok(n)
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    impl From<borsh::maybestd::io::Error> for Error {
+        #[inline]
+        fn from(e: borsh::maybestd::io::Error) -> Self {
+            use borsh::maybestd::io::ErrorKind as Kind;
+            match e.kind() {
+                Kind::InvalidInput => ErrorKind::InvalidInput,
+                Kind::InvalidData => ErrorKind::InvalidData,
+                Kind::WriteZero => ErrorKind::WriteZero,
+                Kind::UnexpectedEof => ErrorKind::UnexpectedEof,
+                _ => ErrorKind::Other,
+            }
+            .into()
+        }
+    }
+    #[cfg(feature = "borsh")]
+    impl From<Error> for borsh::maybestd::io::Error {
+        #[inline]
+        fn from(e: Error) -> Self {
+            use borsh::maybestd::io::ErrorKind as Kind;
+            match e.kind() {
+                ErrorKind::InvalidInput => Kind::InvalidInput,
+                ErrorKind::InvalidData => Kind::InvalidData,
+                ErrorKind::WriteZero => Kind::WriteZero,
+                ErrorKind::UnexpectedEof => Kind::UnexpectedEof,
+                ErrorKind::Other => Kind::Other,
+            }
+            .into()
+        }
+    }
+
+    /// borsh has its own `io` without `std`.
+    #[cfg(feature = "borsh")]
+    pub(crate) struct BorshIo<T>(pub T);
+    #[cfg(feature = "borsh")]
+    impl<W: Write> borsh::maybestd::io::Write for BorshIo<W> {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> borsh::maybestd::io::Result<usize> {
+            Ok(self.0.write(buf)?)
+        }
+        #[inline]
+        fn flush(&mut self) -> borsh::maybestd::io::Result<()> {
+            Ok(self.0.flush()?)
+        }
+    }
+    #[cfg(feature = "borsh")]
+    impl<R: Read> borsh::maybestd::io::Read for BorshIo<R> {
+        #[inline]
+        fn read(&mut self, buf: &mut [u8]) -> borsh::maybestd::io::Result<usize> {
+            Ok(self.0.read(buf)?)
+        }
+    }
+}
+
+/// Wrap a reader or writer for borsh. (`std::io` is shared with `std`)
+#[cfg(all(feature = "borsh", feature = "std"))]
+#[inline]
+pub(crate) fn borsh_io<T>(io: T) -> T {
+    io
+}
+#[cfg(all(feature = "borsh", not(feature = "std")))]
+#[inline]
+pub(crate) fn borsh_io<T>(io: T) -> core_io::BorshIo<T> {
+    core_io::BorshIo(io)
+}
+
+/// Write into any `core::hash::Hasher`.
+///
+/// Every `write` is a separate `Hasher::write`, and hashers such as FxHash depend on that split.
+pub struct HasherWriter<'a, H: ?Sized>(pub &'a mut H);
+impl<H: core::hash::Hasher + ?Sized> Write for HasherWriter<'_, H> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Write into any `digest::Digest` hasher.
+#[cfg(feature = "hash")]
+pub(crate) struct DigestWriter<'a, D>(pub &'a mut D);
+#[cfg(feature = "hash")]
+impl<D: digest::Digest> Write for DigestWriter<'_, D> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        digest::Digest::update(self.0, buf);
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Write into any `digest::Update` hasher.
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub(crate) struct UpdateWriter<'a, U: ?Sized>(pub &'a mut U);
+#[cfg(all(feature = "hash", feature = "bytes"))]
+impl<U: digest::Update + ?Sized> Write for UpdateWriter<'_, U> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Write into a `digest::DynDigest`.
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub(crate) struct DynDigestWriter<'a>(pub &'a mut dyn digest::DynDigest);
+#[cfg(all(feature = "hash", feature = "bytes"))]
+impl Write for DynDigestWriter<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Write into any `digest::Mac`.
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub(crate) struct MacWriter<'a, M>(pub &'a mut M);
+#[cfg(all(feature = "hash", feature = "bytes"))]
+impl<M: digest::Mac> Write for MacWriter<'_, M> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        digest::Mac::update(self.0, buf);
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// # A writer that hashes every byte written through it with `D`.
+#[cfg(feature = "hash")]
+pub struct HashingWriter<W, D> {
+    inner: W,
+    hasher: D,
+}
+#[cfg(feature = "hash")]
+impl<W, D: digest::Digest> HashingWriter<W, D> {
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        HashingWriter {
+            inner,
+            hasher: D::new(),
+        }
+    }
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+    /// The inner writer and the hash of the bytes written so far.
+    #[inline]
+    pub fn finalize(self) -> (W, digest::Output<D>) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+#[cfg(feature = "hash")]
+impl<W: Write, D: digest::Digest> Write for HashingWriter<W, D> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+}
+
+/// # A reader that hashes every byte read through it with `D`.
+#[cfg(feature = "hash")]
+pub struct HashingReader<R, D> {
+    inner: R,
+    hasher: D,
+}
+#[cfg(feature = "hash")]
+impl<R, D: digest::Digest> HashingReader<R, D> {
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: D::new(),
+        }
+    }
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// The inner reader and the hash of the bytes read so far.
+    #[inline]
+    pub fn finalize(self) -> (R, digest::Output<D>) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+#[cfg(feature = "hash")]
+impl<R: Read, D: digest::Digest> Read for HashingReader<R, D> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
@@ -1,9 +1,38 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod enums;
 mod traits;
 
-pub mod error;
+#[cfg(feature = "algorithms")]
+mod algorithm;
 
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub mod chain;
+pub mod codec;
+#[cfg(all(feature = "hash", feature = "bytes", feature = "hex"))]
+pub mod dir;
 #[cfg(feature = "bytes")]
+pub mod explain;
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "hash")]
+pub mod id;
+pub mod io;
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub mod merkle;
+#[cfg(feature = "multihash")]
+pub mod multihash;
+#[cfg(all(feature = "signature", feature = "bytes"))]
+mod signed;
+
+#[cfg(all(feature = "borsh", feature = "json"))]
+pub mod schema;
+
+pub mod error;
+
+#[cfg(feature = "borsh")]
 pub extern crate borsh;
 #[cfg(feature = "hash")]
 pub extern crate digest;
@@ -13,19 +42,48 @@ pub extern crate serde;
 pub extern crate serde_json;
 #[cfg(feature = "yaml")]
 pub extern crate serde_yaml;
+#[cfg(feature = "signature")]
+pub extern crate signature;
 #[cfg(feature = "toml")]
 pub extern crate toml as serde_toml;
+#[cfg(feature = "uuid")]
+pub extern crate uuid;
 
+pub use codec::BinaryCodec;
 pub use traits::{AsBytes, ToStringForBytes};
 
 #[cfg(feature = "bytes")]
 pub use crate::{
     enums::Endian,
-    traits::{ToBorshBytes, ToBytes, ToBytesOptions},
+    explain::{ByteSpan, BytesLayout},
+    traits::{ToBytes, ToBytesOptions},
 };
 
+#[cfg(feature = "borsh")]
+pub use crate::traits::ToBorshBytes;
+
+#[cfg(all(feature = "borsh", feature = "json"))]
+pub use crate::schema::{borsh_dump, borsh_schema_to_json, try_borsh_dump};
+
+#[cfg(all(feature = "hash", feature = "bytes", feature = "hex"))]
+pub use crate::dir::{hash_dir, try_hash_dir};
+#[cfg(all(feature = "hash", feature = "std"))]
+pub use crate::hash::{hash_file, try_hash_file};
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub use crate::hash::{hash_seq, hash_set, try_hash_seq, try_hash_set, Hashed, HashedMut};
 #[cfg(feature = "hash")]
-pub use crate::traits::ToHash;
+pub use crate::{
+    hash::{hash_reader, try_hash_reader, Hash},
+    traits::{HashView, ToHash, ToHashOptions},
+};
+
+#[cfg(feature = "algorithms")]
+pub use crate::algorithm::HashAlgorithm;
+
+#[cfg(all(feature = "signature", feature = "bytes"))]
+pub use crate::signed::Signed;
+#[cfg(feature = "signature")]
+pub use crate::traits::ToSigned;
 
 #[cfg(feature = "json")]
 pub use crate::{serde_json::Value as JsonValue, traits::ToJson};
@@ -0,0 +1,293 @@
+//! # Merkle trees over `ToHash` items.
+//!
+//! - leaf = `D(leaf_prefix || bytes)`, the same bytes as `to_hash`
+//! - node = `D(node_prefix || left || right)`
+//! - an odd node at the end of a level is promoted unchanged.
+//! - the root of an empty tree is `D("")`.
+
+use crate::{error::*, traits::*};
+use alloc::vec::Vec;
+
+use digest::{Digest, Output};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerkleOptions {
+    pub leaf_prefix: &'static [u8],
+    pub node_prefix: &'static [u8],
+}
+impl MerkleOptions {
+    #[inline]
+    pub const fn default() -> Self {
+        MerkleOptions {
+            leaf_prefix: &[0x00],
+            node_prefix: &[0x01],
+        }
+    }
+
+    /// Hash a leaf.
+    #[inline]
+    pub fn try_leaf_hash<D: Digest, T>(&self, item: &T) -> Result<Output<D>>
+    where
+        T: ToHash + ToBytes + Serialize + ?Sized,
+    {
+        let mut hasher = D::new();
+        hasher.update(self.leaf_prefix);
+        item.try_to_hash_into(&mut hasher)?;
+        Ok(hasher.finalize())
+    }
+    #[inline]
+    pub fn leaf_hash<D: Digest, T>(&self, item: &T) -> Output<D>
+    where
+        T: ToHash + ToBytes + Serialize + ?Sized,
+    {
+        self.try_leaf_hash::<D, T>(item).unwrap()
+    }
+
+    /// Hash two children.
+    #[inline]
+    pub fn node_hash<D: Digest>(&self, left: &[u8], right: &[u8]) -> Output<D> {
+        D::new()
+            .chain_update(self.node_prefix)
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+    }
+}
+
+/// # A merkle tree with every level kept for proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree<D: Digest> {
+    options: MerkleOptions,
+    /// `levels[0]` are the leaves, the last level is the root.
+    levels: Vec<Vec<Output<D>>>,
+}
+
+impl<D: Digest> MerkleTree<D> {
+    #[inline]
+    pub fn try_from_iter_with<T>(
+        items: impl IntoIterator<Item = T>,
+        options: MerkleOptions,
+    ) -> Result<Self>
+    where
+        T: ToHash + ToBytes + Serialize,
+    {
+        let leaves = items
+            .into_iter()
+            .map(|item| options.try_leaf_hash::<D, T>(&item))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::from_leaf_hashes_with(leaves, options))
+    }
+    #[inline]
+    pub fn from_iter_with<T>(items: impl IntoIterator<Item = T>, options: MerkleOptions) -> Self
+    where
+        T: ToHash + ToBytes + Serialize,
+    {
+        Self::try_from_iter_with(items, options).unwrap()
+    }
+
+    /// Build from leaves that are already hashed with `options.leaf_hash`.
+    pub fn from_leaf_hashes_with(leaves: Vec<Output<D>>, options: MerkleOptions) -> Self {
+        let mut levels = alloc::vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => options.node_hash::<D>(left, right),
+                    [last] => last.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { options, levels }
+    }
+
+    #[inline]
+    pub fn options(&self) -> MerkleOptions {
+        self.options
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline]
+    pub fn leaves(&self) -> &[Output<D>] {
+        &self.levels[0]
+    }
+    #[inline]
+    pub fn root(&self) -> Output<D> {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => root.clone(),
+            None => D::new().finalize(),
+        }
+    }
+
+    /// Inclusion proof of the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(i ^ 1) {
+                siblings.push(sibling.to_vec());
+            }
+            i /= 2;
+        }
+        Some(MerkleProof {
+            index: index as u64,
+            len: self.len() as u64,
+            siblings,
+        })
+    }
+}
+
+impl<D: Digest, T: ToHash + ToBytes + Serialize> FromIterator<T> for MerkleTree<D> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        Self::from_iter_with(items, MerkleOptions::default())
+    }
+}
+
+/// # An inclusion proof of one leaf.
+///
+/// Serializable with `ToBytes` and `ToJson`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub index: u64,
+    /// Number of leaves in the tree.
+    pub len: u64,
+    /// Sibling hashes from the leaf level up.
+    pub siblings: Vec<Vec<u8>>,
+}
+
+impl ToBytes for MerkleProof {}
+#[cfg(feature = "json")]
+impl ToJson for MerkleProof {}
+
+impl MerkleProof {
+    /// Verify that `item` is included under `root`.
+    #[inline]
+    pub fn verify<D: Digest, T>(&self, root: impl AsBytes, item: &T) -> Result<()>
+    where
+        T: ToHash + ToBytes + Serialize + ?Sized,
+    {
+        self.verify_with::<D, T>(root, item, MerkleOptions::default())
+    }
+    #[inline]
+    pub fn verify_with<D: Digest, T>(
+        &self,
+        root: impl AsBytes,
+        item: &T,
+        options: MerkleOptions,
+    ) -> Result<()>
+    where
+        T: ToHash + ToBytes + Serialize + ?Sized,
+    {
+        let leaf = options.try_leaf_hash::<D, T>(item)?;
+        self.verify_leaf_hash_with::<D>(root, &leaf, options)
+    }
+
+    /// Verify a leaf that is already hashed with `options.leaf_hash`.
+    pub fn verify_leaf_hash_with<D: Digest>(
+        &self,
+        root: impl AsBytes,
+        leaf: &[u8],
+        options: MerkleOptions,
+    ) -> Result<()> {
+        if self.index >= self.len {
+            return Err(Error::InvalidProof);
+        }
+        let mut siblings = self.siblings.iter();
+        let mut hash = leaf.to_vec();
+        let (mut i, mut len) = (self.index, self.len);
+        while len > 1 {
+            if i % 2 == 1 {
+                let left = siblings.next().ok_or(Error::InvalidProof)?;
+                hash = options.node_hash::<D>(left, &hash).to_vec();
+            } else if i + 1 < len {
+                let right = siblings.next().ok_or(Error::InvalidProof)?;
+                hash = options.node_hash::<D>(&hash, right).to_vec();
+            }
+            i /= 2;
+            len = len.div_ceil(2);
+        }
+        if siblings.next().is_some() || hash != root.as_byte_slice() {
+            return Err(Error::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    #[derive(Serialize)]
+    struct Test(u32);
+    impl ToBytes for Test {}
+    impl ToHash for Test {}
+
+    #[test]
+    fn test_merkle_tree() {
+        let options = MerkleOptions::default();
+        let leaf = |i| options.leaf_hash::<Sha256, _>(&Test(i));
+        let node = |l: &[u8], r: &[u8]| options.node_hash::<Sha256>(l, r);
+
+        let tree = (0..3).map(Test).collect::<MerkleTree<Sha256>>();
+        let ab = node(&leaf(0), &leaf(1));
+        assert_eq!(tree.root(), node(&ab, &leaf(2)));
+        assert_eq!(tree.len(), 3);
+
+        let empty = MerkleTree::<Sha256>::from_iter(Vec::<Test>::new());
+        assert_eq!(empty.root(), Sha256::digest(b""));
+
+        let single = MerkleTree::<Sha256>::from_iter([Test(0)]);
+        assert_eq!(single.root(), leaf(0));
+        let proof = single.proof(0).unwrap();
+        assert!(proof.siblings.is_empty());
+        assert!(proof.verify::<Sha256, _>(single.root(), &Test(0)).is_ok());
+    }
+
+    #[test]
+    fn test_merkle_proof() {
+        for len in 1..=9 {
+            let tree = MerkleTree::<Sha256>::from_iter((0..len).map(Test));
+            let root = tree.root();
+            for i in 0..len {
+                let proof = tree.proof(i as usize).unwrap();
+                assert!(proof.verify::<Sha256, _>(root, &Test(i)).is_ok());
+                assert!(proof.verify::<Sha256, _>(root, &Test(len)).is_err());
+
+                let proof = MerkleProof::from_bytes(proof.to_bytes());
+                assert!(proof.verify::<Sha256, _>(root, &Test(i)).is_ok());
+                #[cfg(feature = "json")]
+                assert_eq!(MerkleProof::from_json(proof.to_json()), proof);
+            }
+            assert!(tree.proof(len as usize).is_none());
+        }
+    }
+
+    #[test]
+    fn test_merkle_options() {
+        let options = MerkleOptions {
+            leaf_prefix: b"leaf",
+            node_prefix: b"node",
+        };
+        let tree = MerkleTree::<Sha256>::from_iter_with((0..4).map(Test), options);
+        assert_ne!(tree.root(), MerkleTree::<Sha256>::from_iter((0..4).map(Test)).root());
+
+        let proof = tree.proof(2).unwrap();
+        assert!(proof
+            .verify_with::<Sha256, _>(tree.root(), &Test(2), options)
+            .is_ok());
+        assert!(proof.verify::<Sha256, _>(tree.root(), &Test(2)).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! # Multihash: `varint(code) || varint(len) || digest`.
+//!
+//! `MultihashDigest` gives a digest its multihash code.
+//! Built in for SHA-2, SHA-3, BLAKE2 and BLAKE3.
+
+use crate::{error::*, traits::AsBytes};
+use alloc::vec::Vec;
+
+use digest::{consts::U32, Digest, Output};
+
+/// A digest with a multihash code.
+pub trait MultihashDigest: Digest {
+    const CODE: u64;
+}
+
+macro_rules! impl_multihash_digest {
+    ($($ty:ty => $code:expr,)*) => {
+        $(
+            impl MultihashDigest for $ty {
+                const CODE: u64 = $code;
+            }
+        )*
+    };
+}
+
+impl_multihash_digest! {
+    sha2::Sha224 => 0x1013,
+    sha2::Sha256 => 0x12,
+    sha2::Sha384 => 0x20,
+    sha2::Sha512 => 0x13,
+    sha2::Sha512_224 => 0x1014,
+    sha2::Sha512_256 => 0x1015,
+
+    sha3::Sha3_224 => 0x17,
+    sha3::Sha3_256 => 0x16,
+    sha3::Sha3_384 => 0x15,
+    sha3::Sha3_512 => 0x14,
+    sha3::Keccak224 => 0x1a,
+    sha3::Keccak256 => 0x1b,
+    sha3::Keccak384 => 0x1c,
+    sha3::Keccak512 => 0x1d,
+
+    blake2::Blake2b<U32> => 0xb220,
+    blake2::Blake2b512 => 0xb240,
+    blake2::Blake2s256 => 0xb260,
+
+    blake3::Hasher => 0x1e,
+}
+
+/// Encode a digest output as a multihash.
+#[inline]
+pub fn encode<D: MultihashDigest>(output: &Output<D>) -> Vec<u8> {
+    let mut multihash = Vec::with_capacity(output.len() + 8);
+    write_varint(&mut multihash, D::CODE);
+    write_varint(&mut multihash, output.len() as u64);
+    multihash.extend_from_slice(output);
+    multihash
+}
+
+/// Decode a multihash, checking its code and length against `D`.
+#[inline]
+pub fn try_decode<D: MultihashDigest>(multihash: impl AsBytes) -> Result<Output<D>> {
+    let mut bytes = multihash.as_byte_slice();
+    let code = read_varint(&mut bytes)?;
+    if code != D::CODE {
+        return Err(Error::MultihashCode(D::CODE, code));
+    }
+    let len = read_varint(&mut bytes)?;
+    let size = <D as Digest>::output_size();
+    if len != size as u64 {
+        return Err(Error::HashLength(size, len as usize));
+    }
+    if bytes.len() != size {
+        return Err(Error::InvalidMultihash);
+    }
+    Ok(Output::<D>::clone_from_slice(bytes))
+}
+#[inline]
+pub fn decode<D: MultihashDigest>(multihash: impl AsBytes) -> Output<D> {
+    try_decode::<D>(multihash).unwrap()
+}
+
+/// Unsigned LEB128.
+fn write_varint(buf: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        buf.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+/// Unsigned LEB128, at most 9 bytes as in the multiformats spec.
+fn read_varint(bytes: &mut &[u8]) -> Result<u64> {
+    let mut n = 0u64;
+    for i in 0..9 {
+        let (&byte, rest) = bytes.split_first().ok_or(Error::InvalidMultihash)?;
+        *bytes = rest;
+        n |= u64::from(byte & 0x7f) << (i * 7);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                // not minimal
+                return Err(Error::InvalidMultihash);
+            }
+            return Ok(n);
+        }
+    }
+    Err(Error::InvalidMultihash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint() {
+        for (n, bytes) in [
+            (0x12, &[0x12][..]),
+            (0x80, &[0x80, 0x01]),
+            (0xb220, &[0xa0, 0xe4, 0x02]),
+        ] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, n);
+            assert_eq!(buf, bytes);
+            assert_eq!(read_varint(&mut &buf[..]).unwrap(), n);
+        }
+        assert!(read_varint(&mut &[0x80][..]).is_err());
+        assert!(read_varint(&mut &[0x80, 0x00][..]).is_err());
+    }
+
+    #[test]
+    fn test_multihash() {
+        let output = sha2::Sha256::digest(b"hello");
+        let multihash = encode::<sha2::Sha256>(&output);
+        assert_eq!(multihash[..2], [0x12, 0x20]);
+        assert_eq!(decode::<sha2::Sha256>(&multihash), output);
+
+        assert!(matches!(
+            try_decode::<sha3::Sha3_256>(&multihash),
+            Err(Error::MultihashCode(0x16, 0x12))
+        ));
+        assert!(try_decode::<sha2::Sha256>(&multihash[..33]).is_err());
+
+        let output = blake3::Hasher::digest(b"hello");
+        let multihash = encode::<blake3::Hasher>(&output);
+        assert_eq!(multihash[..2], [0x1e, 0x20]);
+        assert_eq!(decode::<blake3::Hasher>(multihash), output);
+
+        let multihash = encode::<blake2::Blake2b<U32>>(&blake2::Blake2b::<U32>::digest(b"hello"));
+        assert_eq!(multihash[..4], [0xa0, 0xe4, 0x02, 0x20]);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_to_multihash() {
+        use crate::traits::{ToBytes, ToHash};
+
+        #[derive(serde::Serialize)]
+        struct Test(u32);
+        impl ToBytes for Test {}
+        impl ToHash for Test {}
+
+        let multihash = Test(1).to_multihash::<sha2::Sha256>();
+        assert_eq!(
+            decode::<sha2::Sha256>(multihash),
+            Test(1).to_hash::<sha2::Sha256>()
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{error::*, traits::AsBytes};
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use serde_json::{Map, Value as JsonValue};
+
+/// Export a borsh schema as json.
+///
+/// Definitions are tagged the same way serde tags enums, e.g.
+/// `{"Struct": {"fields": {"NamedFields": [["a", "u32"]]}}}`.
+pub fn borsh_schema_to_json(schema: &BorshSchemaContainer) -> JsonValue {
+    let definitions = schema
+        .definitions
+        .iter()
+        .map(|(declaration, definition)| (declaration.clone(), definition_to_json(definition)))
+        .collect::<Map<_, _>>();
+    let mut root = Map::new();
+    root.insert("declaration".to_owned(), schema.declaration.clone().into());
+    root.insert("definitions".to_owned(), definitions.into());
+    root.into()
+}
+
+fn definition_to_json(definition: &Definition) -> JsonValue {
+    let (tag, body) = match definition {
+        Definition::Array { length, elements } => (
+            "Array",
+            serde_json::json!({ "length": length, "elements": elements }),
+        ),
+        Definition::Sequence { elements } => {
+            ("Sequence", serde_json::json!({ "elements": elements }))
+        }
+        Definition::Tuple { elements } => ("Tuple", serde_json::json!({ "elements": elements })),
+        Definition::Enum { variants } => ("Enum", serde_json::json!({ "variants": variants })),
+        Definition::Struct { fields } => {
+            let fields = match fields {
+                Fields::NamedFields(v) => serde_json::json!({ "NamedFields": v }),
+                Fields::UnnamedFields(v) => serde_json::json!({ "UnnamedFields": v }),
+                Fields::Empty => JsonValue::from("Empty"),
+            };
+            ("Struct", serde_json::json!({ "fields": fields }))
+        }
+    };
+    let mut map = Map::new();
+    map.insert(tag.to_owned(), body);
+    map.into()
+}
+
+/// Decode borsh bytes against a schema into a json value.
+///
+/// Named structs become objects, tuples and unnamed structs become arrays,
+/// enums become `{"Variant": value}`. 128-bit integers are rendered as strings.
+/// Schemas nested deeper than 128 levels are rejected.
+pub fn try_borsh_dump(schema: &BorshSchemaContainer, bytes: impl AsBytes) -> Result<JsonValue> {
+    let mut buf = bytes.as_byte_slice();
+    let value = dump(schema, &schema.declaration, &mut buf, 0)?;
+    if !buf.is_empty() {
+        return Err(Error::BorshSchema(format!(
+            "{} trailing bytes after `{}`",
+            buf.len(),
+            schema.declaration
+        )));
+    }
+    Ok(value)
+}
+
+#[inline]
+pub fn borsh_dump(schema: &BorshSchemaContainer, bytes: impl AsBytes) -> JsonValue {
+    try_borsh_dump(schema, bytes).unwrap()
+}
+
+/// Nesting limit of [`try_borsh_dump`], a recursive schema can nest without
+/// reading any bytes.
+const MAX_DEPTH: usize = 128;
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::OutOfBounds(buf.len(), len));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+fn take_array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    let mut array = [0u8; N];
+    array.copy_from_slice(take(buf, N)?);
+    Ok(array)
+}
+
+fn take_len(buf: &mut &[u8]) -> Result<usize> {
+    Ok(u32::from_le_bytes(take_array(buf)?) as usize)
+}
+
+/// Check an untrusted sequence length against the remaining bytes.
+///
+/// Every element takes at least one byte, unless it is zero-sized,
+/// and borsh itself rejects sequences of zero-sized elements.
+fn check_len(
+    schema: &BorshSchemaContainer,
+    elements: &str,
+    len: usize,
+    buf: &[u8],
+    depth: usize,
+) -> Result<()> {
+    if len <= buf.len() {
+        return Ok(());
+    }
+    let mut probe = buf;
+    dump(schema, elements, &mut probe, depth)?;
+    if probe.len() < buf.len() {
+        return Err(Error::OutOfBounds(buf.len(), len));
+    }
+    Err(Error::BorshSchema(format!(
+        "sequence of {len} zero-sized `{elements}`"
+    )))
+}
+
+fn dump(
+    schema: &BorshSchemaContainer,
+    declaration: &str,
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<JsonValue> {
+    if depth > MAX_DEPTH {
+        return Err(Error::BorshSchema(format!(
+            "`{declaration}` nested deeper than {MAX_DEPTH}"
+        )));
+    }
+    let depth = depth + 1;
+    let Some(definition) = schema.definitions.get(declaration) else {
+        return dump_primitive(declaration, buf);
+    };
+    Ok(match definition {
+        Definition::Array { length, elements } => JsonValue::Array(
+            (0..*length)
+                .map(|_| dump(schema, elements, buf, depth))
+                .collect::<Result<_>>()?,
+        ),
+        Definition::Sequence { elements } => {
+            let len = take_len(buf)?;
+            check_len(schema, elements, len, buf, depth)?;
+            JsonValue::Array(
+                (0..len)
+                    .map(|_| dump(schema, elements, buf, depth))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        Definition::Tuple { elements } => JsonValue::Array(
+            elements
+                .iter()
+                .map(|element| dump(schema, element, buf, depth))
+                .collect::<Result<_>>()?,
+        ),
+        Definition::Enum { variants } => {
+            let tag = take_array::<1>(buf)?[0] as usize;
+            let Some((name, variant)) = variants.get(tag) else {
+                return Err(Error::BorshSchema(format!(
+                    "invalid variant index {tag} for `{declaration}`"
+                )));
+            };
+            let mut map = Map::new();
+            map.insert(name.clone(), dump(schema, variant, buf, depth)?);
+            map.into()
+        }
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => fields
+                .iter()
+                .map(|(name, field)| Ok((name.clone(), dump(schema, field, buf, depth)?)))
+                .collect::<Result<Map<_, _>>>()?
+                .into(),
+            Fields::UnnamedFields(fields) => JsonValue::Array(
+                fields
+                    .iter()
+                    .map(|field| dump(schema, field, buf, depth))
+                    .collect::<Result<_>>()?,
+            ),
+            Fields::Empty => JsonValue::Null,
+        },
+    })
+}
+
+fn dump_primitive(declaration: &str, buf: &mut &[u8]) -> Result<JsonValue> {
+    macro_rules! le {
+        ($ty:ty) => {
+            <$ty>::from_le_bytes(take_array(buf)?)
+        };
+    }
+    Ok(match declaration {
+        "nil" => JsonValue::Null,
+        "bool" => match take_array::<1>(buf)?[0] {
+            0 => false.into(),
+            1 => true.into(),
+            v => return Err(Error::BorshSchema(format!("invalid bool value {v}"))),
+        },
+        "u8" => le!(u8).into(),
+        "u16" => le!(u16).into(),
+        "u32" => le!(u32).into(),
+        "u64" => le!(u64).into(),
+        "u128" => le!(u128).to_string().into(),
+        "i8" => le!(i8).into(),
+        "i16" => le!(i16).into(),
+        "i32" => le!(i32).into(),
+        "i64" => le!(i64).into(),
+        "i128" => le!(i128).to_string().into(),
+        "f32" => le!(f32).into(),
+        "f64" => le!(f64).into(),
+        "string" => {
+            let len = take_len(buf)?;
+            core::str::from_utf8(take(buf, len)?)?.into()
+        }
+        _ => {
+            return Err(Error::BorshSchema(format!(
+                "unknown declaration `{declaration}`"
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use borsh::{BorshSchema, BorshSerialize};
+    use std::collections::HashMap;
+
+    #[derive(BorshSerialize, BorshSchema)]
+    struct Test {
+        a: u32,
+        b: String,
+        c: [u8; 4],
+        d: Option<u128>,
+        e: HashMap<u8, bool>,
+        f: Kind,
+    }
+
+    #[derive(BorshSerialize, BorshSchema)]
+    enum Kind {
+        Empty,
+        Pair(i8, f64),
+    }
+
+    #[test]
+    fn test_borsh_dump() {
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [1, 2, 3, 4],
+            d: Some(u128::MAX),
+            e: HashMap::from([(7, true)]),
+            f: Kind::Pair(-1, 0.5),
+        };
+        let bytes = test.try_to_vec().unwrap();
+        let value = borsh_dump(&Test::schema_container(), bytes);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "a": 1,
+                "b": "hello",
+                "c": [1, 2, 3, 4],
+                "d": { "Some": u128::MAX.to_string() },
+                "e": [[7, true]],
+                "f": { "Pair": [-1, 0.5] },
+            })
+        );
+    }
+
+    #[test]
+    fn test_borsh_dump_trailing_bytes() {
+        let mut bytes = 1u32.try_to_vec().unwrap();
+        bytes.push(0);
+        assert!(try_borsh_dump(&u32::schema_container(), bytes).is_err());
+    }
+
+    #[test]
+    fn test_borsh_dump_huge_len() {
+        let bytes = u32::MAX.to_le_bytes();
+        assert!(matches!(
+            try_borsh_dump(&Vec::<()>::schema_container(), bytes),
+            Err(Error::BorshSchema(_))
+        ));
+        assert!(matches!(
+            try_borsh_dump(&Vec::<u8>::schema_container(), bytes),
+            Err(Error::OutOfBounds(0, _))
+        ));
+    }
+
+    #[test]
+    fn test_borsh_dump_recursive_schema() {
+        let schema = BorshSchemaContainer {
+            declaration: "Loop".to_owned(),
+            definitions: [(
+                "Loop".to_owned(),
+                Definition::Tuple {
+                    elements: vec!["Loop".to_owned()],
+                },
+            )]
+            .into(),
+        };
+        assert!(matches!(
+            try_borsh_dump(&schema, [0u8; 0]),
+            Err(Error::BorshSchema(_))
+        ));
+    }
+
+    #[test]
+    fn test_borsh_schema_to_json() {
+        let json = borsh_schema_to_json(&Option::<u8>::schema_container());
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "declaration": "Option<u8>",
+                "definitions": {
+                    "Option<u8>": { "Enum": { "variants": [["None", "nil"], ["Some", "u8"]] } }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{error::*, traits::*};
+use core::ops::Deref;
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+use signature::{Keypair, Signer, Verifier};
+
+/// # A value with its signature and verifying key, over the `ToBytes` encoding of its `HashView`.
+///
+/// It can only be built verified, and deserializing verifies the signature.
+/// That proves the value was signed by `signer`, so check that `signer` is trusted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Signed<T, Sig, V> {
+    value: T,
+    signature: Sig,
+    signer: V,
+}
+
+impl<T, Sig, V> Signed<T, Sig, V>
+where
+    T: ToHash + ToBytes + Serialize,
+    V: Verifier<Sig>,
+{
+    /// Sign `value` with `signer`.
+    #[inline]
+    pub fn try_new<S>(value: T, signer: &S) -> Result<Self>
+    where
+        S: Signer<Sig> + Keypair<VerifyingKey = V>,
+    {
+        let signature = signer.try_sign(&HashView(&value).try_to_bytes()?)?;
+        Ok(Signed {
+            value,
+            signature,
+            signer: signer.verifying_key(),
+        })
+    }
+    #[inline]
+    pub fn new<S>(value: T, signer: &S) -> Self
+    where
+        S: Signer<Sig> + Keypair<VerifyingKey = V>,
+    {
+        Self::try_new(value, signer).unwrap()
+    }
+
+    /// Verify `signature` of `value` against `signer`.
+    #[inline]
+    pub fn try_from_parts(value: T, signature: Sig, signer: V) -> Result<Self> {
+        signer.verify(&HashView(&value).try_to_bytes()?, &signature)?;
+        Ok(Signed {
+            value,
+            signature,
+            signer,
+        })
+    }
+}
+
+impl<T, Sig, V> Signed<T, Sig, V> {
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+    #[inline]
+    pub fn signature(&self) -> &Sig {
+        &self.signature
+    }
+    #[inline]
+    pub fn signer(&self) -> &V {
+        &self.signer
+    }
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+    #[inline]
+    pub fn into_parts(self) -> (T, Sig, V) {
+        (self.value, self.signature, self.signer)
+    }
+}
+
+impl<T, Sig, V> Deref for Signed<T, Sig, V> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T, Sig, V> AsRef<T> for Signed<T, Sig, V> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'de, T, Sig, V> Deserialize<'de> for Signed<T, Sig, V>
+where
+    T: ToHash + ToBytes + Serialize + Deserialize<'de>,
+    Sig: Deserialize<'de>,
+    V: Verifier<Sig> + Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> core::result::Result<Self, De::Error> {
+        #[derive(Deserialize)]
+        struct Unverified<T, Sig, V> {
+            value: T,
+            signature: Sig,
+            signer: V,
+        }
+        let Unverified {
+            value,
+            signature,
+            signer,
+        } = Unverified::deserialize(deserializer)?;
+        Self::try_from_parts(value, signature, signer).map_err(de::Error::custom)
+    }
+}
+
+impl<T: Serialize, Sig: Serialize, V: Serialize> ToBytes for Signed<T, Sig, V> {}
+#[cfg(feature = "json")]
+impl<T: Serialize, Sig: Serialize, V: Serialize> ToJson for Signed<T, Sig, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize)]
+    struct Test {
+        a: u32,
+    }
+    impl ToBytes for Test {}
+    impl ToBorshBytes for Test {}
+    impl ToHash for Test {}
+    impl ToSigned for Test {}
+
+    #[test]
+    fn test_sign() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let test = Test { a: 1 };
+        let signature: Signature = test.sign(&key);
+        assert!(test.verify(&key.verifying_key(), &signature).is_ok());
+        assert!(Test { a: 2 }
+            .verify(&key.verifying_key(), &signature)
+            .is_err());
+
+        let signature: Signature = test.sign_borsh(&key);
+        assert!(test.verify_borsh(&key.verifying_key(), &signature).is_ok());
+    }
+
+    #[test]
+    fn test_signed() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signed: Signed<Test, Signature, VerifyingKey> = Test { a: 1 }.into_signed(&key);
+        assert_eq!(signed.a, 1);
+        assert_eq!(signed.signer(), &key.verifying_key());
+
+        let bytes = signed.to_bytes();
+        assert_eq!(Signed::from_bytes(&bytes), signed);
+        #[cfg(feature = "json")]
+        assert_eq!(Signed::from_json(signed.to_json()), signed);
+
+        let (_, signature, signer) = signed.into_parts();
+        assert!(Signed::try_from_parts(Test { a: 2 }, signature, signer).is_err());
+
+        #[derive(Serialize)]
+        struct Forged {
+            value: Test,
+            signature: Signature,
+            signer: VerifyingKey,
+        }
+        impl ToBytes for Forged {}
+        let forged = Forged {
+            value: Test { a: 2 },
+            signature,
+            signer,
+        };
+        assert!(
+            Signed::<Test, Signature, VerifyingKey>::try_from_bytes(forged.to_bytes()).is_err()
+        );
+    }
+}
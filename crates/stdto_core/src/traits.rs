@@ -3,20 +3,60 @@
 use crate::{
     enums::{Endian, HexMode},
     error::*,
+    io,
 };
-use std::{fmt, io};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str};
 
-use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "borsh")]
+use borsh::{schema::BorshSchemaContainer, BorshDeserialize, BorshSchema, BorshSerialize};
 
 #[cfg(feature = "serde")]
 use serde::{de::DeserializeOwned, Serialize};
 
+#[cfg(feature = "bytes")]
+use crate::explain::BytesLayout;
+#[cfg(feature = "bytes")]
+use crate::io::HasherWriter;
 #[cfg(feature = "bytes")]
 use bincode::Options;
+#[cfg(feature = "bytes")]
+use xxhash_rust::xxh3::Xxh3;
 
 #[cfg(feature = "hash")]
 use digest::{Digest, Output};
 
+#[cfg(feature = "algorithms")]
+use crate::algorithm::HashAlgorithm;
+#[cfg(all(feature = "hash", feature = "borsh"))]
+use crate::codec::Borsh;
+#[cfg(all(feature = "hash", feature = "toml"))]
+use crate::codec::Toml;
+#[cfg(all(feature = "hash", feature = "yaml"))]
+use crate::codec::Yaml;
+#[cfg(all(feature = "hash", feature = "json"))]
+use crate::codec::{CanonicalJson, Json};
+#[cfg(all(feature = "hash", feature = "bytes"))]
+use crate::io::{DynDigestWriter, MacWriter, UpdateWriter};
+#[cfg(all(feature = "multihash", feature = "bytes"))]
+use crate::multihash::{self, MultihashDigest};
+#[cfg(feature = "hash")]
+use crate::{codec::BinaryCodec, hash::Hash, io::DigestWriter};
+#[cfg(all(feature = "hash", feature = "bytes"))]
+use digest::core_api::BlockSizeUser;
+#[cfg(all(feature = "hash", feature = "bytes"))]
+use hmac::{Mac, SimpleHmac};
+#[cfg(all(feature = "hash", feature = "bytes", feature = "hex"))]
+use subtle::ConstantTimeEq;
+
+#[cfg(all(feature = "signature", feature = "bytes"))]
+use crate::signed::Signed;
+#[cfg(feature = "signature")]
+use signature::{Keypair, Signer, Verifier};
+
 #[cfg(feature = "json")]
 use serde_json::Value as JsonValue;
 
@@ -26,6 +66,13 @@ use serde_yaml::Value as YamlValue;
 #[cfg(feature = "toml")]
 use serde_toml::Value as TomlValue;
 
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+};
+
 macro_rules! serialize {
     (data: $self:expr, option: $option:expr) => {
         $option
@@ -59,6 +106,99 @@ macro_rules! deserialize {
     };
 }
 
+#[cfg(feature = "async")]
+#[allow(dead_code)]
+async fn write_all_async(
+    mut writer: impl AsyncWrite + Unpin,
+    bytes: Result<Vec<u8>>,
+) -> Result<()> {
+    writer.write_all(&bytes?).await?;
+    writer.flush().await.map_err(Error::Io)
+}
+
+#[cfg(feature = "async")]
+#[allow(dead_code)]
+async fn read_all_async<T>(
+    mut reader: impl AsyncRead + Unpin,
+    from: impl FnOnce(Vec<u8>) -> Result<T>,
+) -> Result<T> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await?;
+    from(buf)
+}
+
+/// Decode exactly one value, like the sync `*_from` readers.
+///
+/// Decoding is retried on the buffered bytes until it no longer runs out of input,
+/// and only the bytes of the value are consumed from `reader`.
+/// A retry waits until the bytes the decoder asked for are buffered, so a long string or
+/// byte vector is decoded about once. A value of many small fields is still decoded again
+/// per `fill_buf`, so read those through a buffer close to the value size.
+#[cfg(feature = "async")]
+#[allow(dead_code)]
+async fn read_one_async<T>(
+    mut reader: impl AsyncBufRead + Unpin,
+    from: impl Fn(&mut Partial) -> Result<T>,
+) -> Result<T> {
+    let mut buf = Vec::new();
+    let mut needed = 0;
+    loop {
+        let available = reader.fill_buf().await?;
+        let eof = available.is_empty();
+        let len = available.len();
+        if !eof && buf.len() + len < needed {
+            // still inside the value, no need to decode yet
+            buf.extend_from_slice(available);
+            reader.consume(len);
+            continue;
+        }
+        let mut partial = Partial {
+            inner: std::io::Read::chain(buf.as_slice(), available),
+            read: 0,
+            needed: 0,
+        };
+        match from(&mut partial) {
+            Ok(value) => {
+                let used = partial.read.saturating_sub(buf.len());
+                reader.consume(used);
+                return Ok(value);
+            }
+            Err(_) if partial.needed > 0 && !eof => {
+                needed = partial.needed;
+                buf.extend_from_slice(available);
+                reader.consume(len);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The bytes buffered so far, noting how many a decoder wanted when it ran out.
+#[cfg(feature = "async")]
+struct Partial<'a> {
+    inner: std::io::Chain<&'a [u8], &'a [u8]>,
+    read: usize,
+    needed: usize,
+}
+#[cfg(feature = "async")]
+impl std::io::Read for Partial<'_> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n;
+        if n == 0 && !buf.is_empty() {
+            self.needed = self.needed.max(self.read + buf.len());
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "async")]
+#[allow(dead_code)]
+async fn unwrap_async<T>(future: impl Future<Output = Result<T>>) -> T {
+    future.await.unwrap()
+}
+
 /// # A trait that can convert to a slice of bytes.
 pub trait AsBytes {
     fn as_byte_slice(&self) -> &[u8];
@@ -81,6 +221,18 @@ pub trait AsBytes {
     fn to_bytes_into(&self, writer: impl io::Write) {
         self.try_to_bytes_into(writer).unwrap()
     }
+
+    /// Hash the raw bytes, without any encoding. (same as `sha256sum`, ...)
+    #[cfg(feature = "hash")]
+    #[inline]
+    fn digest<T: Digest>(&self) -> Output<T> {
+        T::digest(self.as_byte_slice())
+    }
+    #[cfg(feature = "hash")]
+    #[inline]
+    fn digest_into<T: Digest>(&self, hasher: &mut T) {
+        hasher.update(self.as_byte_slice())
+    }
 }
 
 /// implement `AsBytes` for `impl AsRef<[u8]>`
@@ -95,7 +247,7 @@ impl<T: AsRef<[u8]>> AsBytes for T {
 pub trait ToStringForBytes: AsBytes {
     #[inline]
     fn try_as_str(&self) -> Result<&str> {
-        std::str::from_utf8(self.as_byte_slice()).map_err(Error::Utf8)
+        str::from_utf8(self.as_byte_slice()).map_err(Error::Utf8)
     }
     #[inline]
     fn try_to_string(&self) -> Result<String> {
@@ -155,6 +307,8 @@ impl ToBytesOptions {
 
 #[cfg(feature = "bytes")]
 /// # A trait that can de/serialize something with bytes. (default: little endian)
+///
+/// Needs `std`, because bincode 1 only works over `std::io`. Use `ToBorshBytes` without `std`.
 pub trait ToBytes {
     const OPTIONS: ToBytesOptions = ToBytesOptions {
         ..ToBytesOptions::default()
@@ -370,183 +524,1094 @@ pub trait ToBytes {
         }
     }
     #[inline]
-    fn try_from_bytes_from(reader: impl io::Read) -> Result<Self>
+    fn try_from_bytes_from(reader: impl io::Read) -> Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        match Self::OPTIONS.endian {
+            Endian::Big => Self::try_from_be_bytes_from(reader),
+            Endian::Little => Self::try_from_le_bytes_from(reader),
+            Endian::Native => Self::try_from_ne_bytes_from(reader),
+        }
+    }
+    // --------------------------------------------------
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8>
+    where
+        Self: Serialize,
+    {
+        self.try_to_bytes().unwrap()
+    }
+    #[inline]
+    fn to_bytes_into(&self, writer: impl io::Write)
+    where
+        Self: Serialize,
+    {
+        self.try_to_bytes_into(writer).unwrap()
+    }
+    #[inline]
+    fn from_bytes(bytes: impl AsBytes) -> Self
+    where
+        Self: DeserializeOwned,
+    {
+        Self::try_from_bytes(bytes).unwrap()
+    }
+    #[inline]
+    fn from_bytes_from(reader: impl io::Read) -> Self
+    where
+        Self: DeserializeOwned,
+    {
+        Self::try_from_bytes_from(reader).unwrap()
+    }
+
+    // --- explain ---
+    /// Serialize to bytes with each byte range labelled by its field path and type.
+    #[inline]
+    fn try_explain_bytes(&self) -> Result<BytesLayout>
+    where
+        Self: Serialize,
+    {
+        BytesLayout::try_new(self, Self::OPTIONS.endian)
+    }
+    #[inline]
+    fn explain_bytes(&self) -> BytesLayout
+    where
+        Self: Serialize,
+    {
+        self.try_explain_bytes().unwrap()
+    }
+
+    /// Non-cryptographic 64-bit hash (xxh3) of the bytes, stable across runs.
+    ///
+    /// It is also stable across platforms, unless `OPTIONS.endian` is `Endian::Native`.
+    #[inline]
+    fn try_to_u64_hash(&self) -> Result<u64>
+    where
+        Self: Serialize,
+    {
+        let mut hasher = Xxh3::new();
+        self.try_to_bytes_into(HasherWriter(&mut hasher))?;
+        Ok(hasher.digest())
+    }
+    #[inline]
+    fn to_u64_hash(&self) -> u64
+    where
+        Self: Serialize,
+    {
+        self.try_to_u64_hash().unwrap()
+    }
+
+    /// The bytes and their hash (`bytes.digest::<T>()`) in one pass.
+    ///
+    /// It hashes the raw bytes, so it is `to_hash` only without a domain or `#[hash(skip)]`.
+    #[cfg(feature = "hash")]
+    #[inline]
+    fn try_to_bytes_and_hash<T: Digest>(&self) -> Result<(Vec<u8>, Output<T>)>
+    where
+        Self: Serialize,
+    {
+        let mut writer = io::HashingWriter::<_, T>::new(Vec::new());
+        self.try_to_bytes_into(&mut writer)?;
+        Ok(writer.finalize())
+    }
+    #[cfg(feature = "hash")]
+    #[inline]
+    fn to_bytes_and_hash<T: Digest>(&self) -> (Vec<u8>, Output<T>)
+    where
+        Self: Serialize,
+    {
+        self.try_to_bytes_and_hash::<T>().unwrap()
+    }
+
+    /// Read from `reader` and verify the hash of the bytes read against the raw `expected` bytes.
+    /// Fails with `Error::HashMismatch`.
+    #[cfg(all(feature = "hash", feature = "hex"))]
+    #[inline]
+    fn try_from_bytes_verified<T: Digest>(
+        reader: impl io::Read,
+        expected: impl AsBytes,
+    ) -> Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        let mut reader = io::HashingReader::<_, T>::new(reader);
+        let value = Self::try_from_bytes_from(&mut reader)?;
+        verify_output(&reader.finalize().1, expected.as_byte_slice())?;
+        Ok(value)
+    }
+    #[cfg(all(feature = "hash", feature = "hex"))]
+    #[inline]
+    fn from_bytes_verified<T: Digest>(reader: impl io::Read, expected: impl AsBytes) -> Self
+    where
+        Self: DeserializeOwned,
+    {
+        Self::try_from_bytes_verified::<T>(reader, expected).unwrap()
+    }
+    /// Same as `try_from_bytes_verified`, against hex in any `HexMode`.
+    #[cfg(all(feature = "hash", feature = "hex"))]
+    #[inline]
+    fn try_from_bytes_verified_hex<T: Digest>(
+        reader: impl io::Read,
+        expected: impl AsBytes,
+    ) -> Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        let mut reader = io::HashingReader::<_, T>::new(reader);
+        let value = Self::try_from_bytes_from(&mut reader)?;
+        verify_output_hex(&reader.finalize().1, expected)?;
+        Ok(value)
+    }
+    #[cfg(all(feature = "hash", feature = "hex"))]
+    #[inline]
+    fn from_bytes_verified_hex<T: Digest>(reader: impl io::Read, expected: impl AsBytes) -> Self
+    where
+        Self: DeserializeOwned,
+    {
+        Self::try_from_bytes_verified_hex::<T>(reader, expected).unwrap()
+    }
+
+    // --- async ---
+    /// Serialize to an async writer.
+    ///
+    /// The value is encoded in memory, then written with `AsyncWriteExt::write_all`.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_bytes_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Serialize,
+    {
+        write_all_async(writer, self.try_to_bytes())
+    }
+    /// Read exactly one value, like `try_from_bytes_from`.
+    ///
+    /// Bytes after the value stay in `reader`, so keep reading from the same buffered reader.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_bytes_async<R: AsyncBufRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        read_one_async(reader, |partial| Self::try_from_bytes_from(partial))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_bytes_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: Serialize,
+    {
+        unwrap_async(self.try_to_bytes_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_bytes_async<R: AsyncBufRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Self> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        unwrap_async(Self::try_from_bytes_async(reader))
+    }
+}
+
+#[cfg(feature = "borsh")]
+pub trait ToBorshBytes {
+    /// BorshSerialize to bytes.
+    #[inline]
+    fn try_to_borsh_bytes(&self) -> Result<Vec<u8>>
+    where
+        Self: BorshSerialize,
+    {
+        self.try_to_vec().map_err(Error::from)
+    }
+    #[inline]
+    fn try_to_borsh_bytes_into(&self, mut writer: impl io::Write) -> Result<()>
+    where
+        Self: BorshSerialize,
+    {
+        self.serialize(&mut io::borsh_io(&mut writer))
+            .map_err(Error::from)
+    }
+    // ---------------------
+    #[inline]
+    fn to_borsh_bytes(&self) -> Vec<u8>
+    where
+        Self: BorshSerialize,
+    {
+        self.try_to_borsh_bytes().unwrap()
+    }
+    #[inline]
+    fn to_borsh_bytes_into(&self, writer: impl io::Write)
+    where
+        Self: BorshSerialize,
+    {
+        self.try_to_borsh_bytes_into(writer).unwrap()
+    }
+    // ---------------------
+
+    /// BorshDeserialize from bytes.
+    #[inline]
+    fn try_from_borsh_bytes(bytes: impl AsBytes) -> Result<Self>
+    where
+        Self: BorshDeserialize,
+    {
+        BorshDeserialize::try_from_slice(bytes.as_byte_slice()).map_err(Error::from)
+    }
+    #[inline]
+    fn try_from_borsh_bytes_from(mut reader: impl io::Read) -> Result<Self>
+    where
+        Self: BorshDeserialize,
+    {
+        BorshDeserialize::deserialize_reader(&mut io::borsh_io(&mut reader)).map_err(Error::from)
+    }
+    // ---------------------
+    #[inline]
+    fn from_borsh_bytes(bytes: impl AsBytes) -> Self
+    where
+        Self: BorshDeserialize,
+    {
+        Self::try_from_borsh_bytes(bytes).unwrap()
+    }
+    #[inline]
+    fn from_borsh_bytes_from(reader: impl io::Read) -> Self
+    where
+        Self: BorshDeserialize,
+    {
+        Self::try_from_borsh_bytes_from(reader).unwrap()
+    }
+
+    // --- async ---
+    /// BorshSerialize to an async writer.
+    ///
+    /// The value is encoded in memory, then written with `AsyncWriteExt::write_all`.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_borsh_bytes_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: BorshSerialize,
+    {
+        write_all_async(writer, self.try_to_borsh_bytes())
+    }
+    /// Read exactly one value, like `try_from_borsh_bytes_from`.
+    ///
+    /// Bytes after the value stay in `reader`, so keep reading from the same buffered reader.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_borsh_bytes_async<R: AsyncBufRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: BorshDeserialize,
+    {
+        read_one_async(reader, |partial| Self::try_from_borsh_bytes_from(partial))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_borsh_bytes_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: BorshSerialize,
+    {
+        unwrap_async(self.try_to_borsh_bytes_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_borsh_bytes_async<R: AsyncBufRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Self> + Send
+    where
+        Self: BorshDeserialize,
+    {
+        unwrap_async(Self::try_from_borsh_bytes_async(reader))
+    }
+    /// BorshSchema of this type.
+    ///
+    /// `#[stdto::borsh_bytes(schema)]` derives it. It is opt-in because borsh 0.10
+    /// has no schema for `BTreeMap`, `BTreeSet` and other common field types.
+    #[inline]
+    fn borsh_schema() -> BorshSchemaContainer
+    where
+        Self: BorshSchema,
+    {
+        Self::schema_container()
+    }
+    #[cfg(feature = "json")]
+    #[inline]
+    fn borsh_schema_json() -> JsonValue
+    where
+        Self: BorshSchema,
+    {
+        crate::schema::borsh_schema_to_json(&Self::borsh_schema())
+    }
+    /// Decode borsh bytes against the schema of this type, without deserializing into it.
+    #[cfg(feature = "json")]
+    #[inline]
+    fn try_borsh_dump(bytes: impl AsBytes) -> Result<JsonValue>
+    where
+        Self: BorshSchema,
+    {
+        crate::schema::try_borsh_dump(&Self::borsh_schema(), bytes)
+    }
+    #[cfg(feature = "json")]
+    #[inline]
+    fn borsh_dump(bytes: impl AsBytes) -> JsonValue
+    where
+        Self: BorshSchema,
+    {
+        Self::try_borsh_dump(bytes).unwrap()
+    }
+}
+
+#[cfg(feature = "hash")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToHashOptions {
+    /// Domain separation tag, fed into the hasher before the encoding.
+    pub domain: Option<&'static str>,
+}
+#[cfg(feature = "hash")]
+impl ToHashOptions {
+    #[inline]
+    pub const fn default() -> Self {
+        ToHashOptions { domain: None }
+    }
+}
+
+/// Write a domain tag before the encoding. (u64 little endian length, then the tag)
+#[cfg(feature = "hash")]
+#[inline]
+fn write_domain(mut writer: impl io::Write, domain: &[u8]) -> Result<()> {
+    writer.write_all(&(domain.len() as u64).to_le_bytes())?;
+    writer.write_all(domain)?;
+    Ok(())
+}
+
+/// Compare `actual` in constant time against the raw `expected` bytes.
+#[cfg(all(feature = "hash", feature = "bytes", feature = "hex"))]
+#[inline]
+fn verify_output(actual: &[u8], expected: &[u8]) -> Result<()> {
+    if bool::from(actual.ct_eq(expected)) {
+        Ok(())
+    } else {
+        Err(Error::HashMismatch {
+            expected: expected.to_hex(),
+            actual: actual.to_hex(),
+        })
+    }
+}
+/// Same as `verify_output`, against hex in any `HexMode`.
+#[cfg(all(feature = "hash", feature = "bytes", feature = "hex"))]
+#[inline]
+fn verify_output_hex(actual: &[u8], expected: impl AsBytes) -> Result<()> {
+    verify_output(actual, &Vec::<u8>::try_from_hex(expected)?)
+}
+
+/// HMAC over the domain tag and the bincode encoding, like `to_hash`.
+#[cfg(all(feature = "hash", feature = "bytes"))]
+#[inline]
+fn hmac<V, T>(value: &V, key: &[u8]) -> Result<SimpleHmac<T>>
+where
+    V: ToHash + ToBytes + Serialize + ?Sized,
+    T: Digest + BlockSizeUser,
+{
+    let mut mac = <SimpleHmac<T> as Mac>::new_from_slice(key)?;
+    let mut writer = MacWriter(&mut mac);
+    if let Some(domain) = <V as ToHash>::OPTIONS.domain {
+        write_domain(&mut writer, domain.as_bytes())?;
+    }
+    value.try_to_hash_bytes_into(writer)?;
+    Ok(mac)
+}
+
+/// # The part of a value that `ToHash` hashes.
+///
+/// The whole value, or only the fields selected by `#[hash(skip)]` / `#[hash(only)]`.
+/// Every hash of `ToHash`, whatever the codec, encodes this view.
+#[cfg(feature = "hash")]
+#[derive(Debug, Clone, Copy)]
+pub struct HashView<'a, T: ?Sized>(pub &'a T);
+
+#[cfg(all(feature = "hash", feature = "serde"))]
+impl<T: ToHash + Serialize + ?Sized> Serialize for HashView<'_, T> {
+    #[inline]
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        self.0.serialize_hash_view(serializer)
+    }
+}
+#[cfg(all(feature = "hash", feature = "borsh"))]
+impl<T: ToHash + BorshSerialize + ?Sized> BorshSerialize for HashView<'_, T> {
+    #[inline]
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        self.0.borsh_serialize_hash_view(writer)
+    }
+}
+#[cfg(all(feature = "hash", feature = "bytes"))]
+impl<T: ToBytes + ?Sized> ToBytes for HashView<'_, T> {
+    const OPTIONS: ToBytesOptions = T::OPTIONS;
+}
+
+/// A reference encodes like the value, so owned and borrowed items mix. (e.g. `hash_seq`)
+#[cfg(feature = "bytes")]
+impl<T: ToBytes + ?Sized> ToBytes for &T {
+    const OPTIONS: ToBytesOptions = T::OPTIONS;
+}
+/// A reference hashes the `HashView` of the value.
+///
+/// Borsh hashing through `&T` is a compile error, since `T: BorshSerialize` is not known here:
+/// call it on `T`.
+#[cfg(all(feature = "hash", feature = "bytes"))]
+impl<T: ToHash + Serialize + ?Sized> ToHash for &T {
+    const OPTIONS: ToHashOptions = T::OPTIONS;
+
+    #[inline]
+    fn serialize_hash_view<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        (**self).serialize_hash_view(serializer)
+    }
+    #[inline]
+    fn borsh_serialize_hash_view<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        struct Unsupported<T: ?Sized>(core::marker::PhantomData<T>);
+        impl<T: ?Sized> Unsupported<T> {
+            const ERROR: () = panic!("borsh hashing through `&T` is not supported, call it on `T`");
+        }
+        let () = Unsupported::<T>::ERROR;
+        let _ = writer;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hash")]
+/// # A trait that can hash bytes.
+///
+/// With `OPTIONS.domain`, every hash is prefixed with the domain tag.
+pub trait ToHash {
+    const OPTIONS: ToHashOptions = ToHashOptions {
+        ..ToHashOptions::default()
+    };
+
+    /// Serialize the `HashView` of `self`. (default: `self`)
+    ///
+    /// `#[hash(skip)]` and `#[hash(only)]` fields override it with the selected fields.
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn serialize_hash_view<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error>
+    where
+        Self: Serialize,
+    {
+        self.serialize(serializer)
+    }
+    /// Serialize the `HashView` of `self` with borsh. (default: `self`)
+    ///
+    /// `#[hash(skip)]` and `#[hash(only)]` fields override it only with `#[hash(borsh)]`.
+    #[cfg(feature = "borsh")]
+    #[inline]
+    fn borsh_serialize_hash_view<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()>
+    where
+        Self: BorshSerialize,
+    {
+        BorshSerialize::serialize(self, writer)
+    }
+
+    /// Hash the `HashView` encoded with any `BinaryCodec`.
+    #[inline]
+    fn try_to_hash_with<C, T: Digest>(&self) -> Result<Output<T>>
+    where
+        C: for<'a> BinaryCodec<HashView<'a, Self>>,
+    {
+        let mut hasher = T::new();
+        self.try_to_hash_with_into::<C, T>(&mut hasher)?;
+        Ok(hasher.finalize())
+    }
+    #[inline]
+    fn try_to_hash_with_into<C, T: Digest>(&self, hasher: &mut T) -> Result<()>
+    where
+        C: for<'a> BinaryCodec<HashView<'a, Self>>,
+    {
+        let mut writer = DigestWriter(hasher);
+        if let Some(domain) = <Self as ToHash>::OPTIONS.domain {
+            write_domain(&mut writer, domain.as_bytes())?;
+        }
+        C::try_encode_into(&HashView(self), writer)
+    }
+    #[inline]
+    fn to_hash_with<C, T: Digest>(&self) -> Output<T>
+    where
+        C: for<'a> BinaryCodec<HashView<'a, Self>>,
+    {
+        self.try_to_hash_with::<C, T>().unwrap()
+    }
+    #[inline]
+    fn to_hash_with_into<C, T: Digest>(&self, hasher: &mut T)
+    where
+        C: for<'a> BinaryCodec<HashView<'a, Self>>,
+    {
+        self.try_to_hash_with_into::<C, T>(hasher).unwrap()
+    }
+
+    // Bincode
+    /// The bytes `to_hash` feeds into the hasher. (default: `to_bytes` of the `HashView`)
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_bytes_into(&self, writer: impl io::Write) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        HashView(self).try_to_bytes_into(writer)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash<T: Digest>(&self) -> Result<Output<T>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut hasher = T::new();
+        self.try_to_hash_into(&mut hasher)?;
+        Ok(hasher.finalize())
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut writer = DigestWriter(hasher);
+        if let Some(domain) = <Self as ToHash>::OPTIONS.domain {
+            write_domain(&mut writer, domain.as_bytes())?;
+        }
+        self.try_to_hash_bytes_into(writer)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash<T: Digest>(&self) -> Output<T>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash::<T>().unwrap()
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_into<T: Digest>(&self, hasher: &mut T)
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_into(hasher).unwrap()
+    }
+
+    /// Same as `to_hash`, as a `Hash<D, Self>`.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_typed_hash<T: Digest>(&self) -> Result<Hash<T, Self>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash::<T>().map(Hash::new)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn typed_hash<T: Digest>(&self) -> Hash<T, Self>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_typed_hash::<T>().unwrap()
+    }
+
+    /// `to_hash` as a self-describing multihash.
+    #[cfg(all(feature = "multihash", feature = "bytes"))]
+    #[inline]
+    fn try_to_multihash<T: MultihashDigest>(&self) -> Result<Vec<u8>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        Ok(multihash::encode::<T>(&self.try_to_hash::<T>()?))
+    }
+    #[cfg(all(feature = "multihash", feature = "bytes"))]
+    #[inline]
+    fn to_multihash<T: MultihashDigest>(&self) -> Vec<u8>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_multihash::<T>().unwrap()
+    }
+
+    /// `to_hash` into any `digest::Update` hasher, e.g. one without `Digest`.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_update_into<U: digest::Update + ?Sized>(&self, hasher: &mut U) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut writer = UpdateWriter(hasher);
+        if let Some(domain) = <Self as ToHash>::OPTIONS.domain {
+            write_domain(&mut writer, domain.as_bytes())?;
+        }
+        self.try_to_hash_bytes_into(writer)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_update_into<U: digest::Update + ?Sized>(&self, hasher: &mut U)
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_update_into(hasher).unwrap()
+    }
+
+    /// `to_hash` into a hasher chosen at runtime.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_dyn_into(&self, hasher: &mut dyn digest::DynDigest) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut writer = DynDigestWriter(hasher);
+        if let Some(domain) = <Self as ToHash>::OPTIONS.domain {
+            write_domain(&mut writer, domain.as_bytes())?;
+        }
+        self.try_to_hash_bytes_into(writer)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_dyn_into(&self, hasher: &mut dyn digest::DynDigest)
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_dyn_into(hasher).unwrap()
+    }
+    /// `to_hash` with an extendable-output function (BLAKE3, SHAKE, K12, ...), `out_len` bytes long.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_xof<X>(&self, out_len: usize) -> Result<Vec<u8>>
+    where
+        Self: ToBytes + Serialize,
+        X: digest::ExtendableOutput + digest::Update + Default,
+    {
+        let mut out = alloc::vec![0; out_len];
+        self.try_to_hash_xof_into::<X>(&mut out)?;
+        Ok(out)
+    }
+    /// Fill `out` with the XOF output. A longer output starts with the shorter one.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_xof_into<X>(&self, out: &mut [u8]) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+        X: digest::ExtendableOutput + digest::Update + Default,
+    {
+        let mut hasher = X::default();
+        self.try_to_hash_update_into(&mut hasher)?;
+        hasher.finalize_xof_into(out);
+        Ok(())
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_xof<X>(&self, out_len: usize) -> Vec<u8>
+    where
+        Self: ToBytes + Serialize,
+        X: digest::ExtendableOutput + digest::Update + Default,
+    {
+        self.try_to_hash_xof::<X>(out_len).unwrap()
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_xof_into<X>(&self, out: &mut [u8])
+    where
+        Self: ToBytes + Serialize,
+        X: digest::ExtendableOutput + digest::Update + Default,
+    {
+        self.try_to_hash_xof_into::<X>(out).unwrap()
+    }
+
+    /// `to_hash` with `algorithm`, the same bytes as `to_hash::<D>` for its `D`.
+    #[cfg(all(feature = "algorithms", feature = "bytes"))]
+    #[inline]
+    fn try_to_hash_dyn(&self, algorithm: HashAlgorithm) -> Result<Vec<u8>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut hasher = algorithm.hasher();
+        self.try_to_hash_dyn_into(hasher.as_mut())?;
+        Ok(hasher.finalize().into_vec())
+    }
+    #[cfg(all(feature = "algorithms", feature = "bytes"))]
+    #[inline]
+    fn to_hash_dyn(&self, algorithm: HashAlgorithm) -> Vec<u8>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_dyn(algorithm).unwrap()
+    }
+
+    // Bincode with an ad-hoc domain tag (instead of `OPTIONS.domain`)
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_with_domain<T: Digest>(&self, domain: impl AsBytes) -> Result<Output<T>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut hasher = T::new();
+        self.try_to_hash_with_domain_into(domain, &mut hasher)?;
+        Ok(hasher.finalize())
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hash_with_domain_into<T: Digest>(
+        &self,
+        domain: impl AsBytes,
+        hasher: &mut T,
+    ) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        let mut writer = DigestWriter(hasher);
+        write_domain(&mut writer, domain.as_byte_slice())?;
+        self.try_to_hash_bytes_into(writer)
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_with_domain<T: Digest>(&self, domain: impl AsBytes) -> Output<T>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_with_domain::<T>(domain).unwrap()
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hash_with_domain_into<T: Digest>(&self, domain: impl AsBytes, hasher: &mut T)
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hash_with_domain_into(domain, hasher).unwrap()
+    }
+
+    // HMAC over the same bytes as `to_hash`
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn try_to_hmac<T: Digest + BlockSizeUser>(&self, key: impl AsBytes) -> Result<Output<T>>
+    where
+        Self: ToBytes + Serialize,
+    {
+        Ok(hmac::<Self, T>(self, key.as_byte_slice())?
+            .finalize()
+            .into_bytes())
+    }
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn to_hmac<T: Digest + BlockSizeUser>(&self, key: impl AsBytes) -> Output<T>
+    where
+        Self: ToBytes + Serialize,
+    {
+        self.try_to_hmac::<T>(key).unwrap()
+    }
+    /// Verify an HMAC tag in constant time.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn verify_hmac<T: Digest + BlockSizeUser>(
+        &self,
+        key: impl AsBytes,
+        tag: impl AsBytes,
+    ) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        hmac::<Self, T>(self, key.as_byte_slice())?
+            .verify_slice(tag.as_byte_slice())
+            .map_err(|_| Error::MacMismatch)
+    }
+    /// Verify `to_hash` in constant time against the raw `expected` bytes.
+    #[cfg(all(feature = "bytes", feature = "hex"))]
+    #[inline]
+    fn verify_hash<T: Digest>(&self, expected: impl AsBytes) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        verify_output(&self.try_to_hash::<T>()?, expected.as_byte_slice())
+    }
+    /// Verify `to_hash` in constant time against hex in any `HexMode`.
+    #[cfg(all(feature = "bytes", feature = "hex"))]
+    #[inline]
+    fn verify_hash_hex<T: Digest>(&self, expected: impl AsBytes) -> Result<()>
+    where
+        Self: ToBytes + Serialize,
+    {
+        verify_output_hex(&self.try_to_hash::<T>()?, expected)
+    }
+
+    // Borsh
+    #[cfg(feature = "borsh")]
+    #[inline]
+    fn try_to_borsh_hash<T: Digest>(&self) -> Result<Output<T>>
+    where
+        Self: ToBorshBytes + BorshSerialize,
+    {
+        self.try_to_hash_with::<Borsh, T>()
+    }
+    #[cfg(feature = "borsh")]
+    #[inline]
+    fn try_to_borsh_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
+    where
+        Self: ToBorshBytes + BorshSerialize,
+    {
+        self.try_to_hash_with_into::<Borsh, T>(hasher)
+    }
+    #[cfg(feature = "borsh")]
+    #[inline]
+    fn to_borsh_hash<T: Digest>(&self) -> Output<T>
+    where
+        Self: ToBorshBytes + BorshSerialize,
+    {
+        self.to_hash_with::<Borsh, T>()
+    }
+    #[cfg(feature = "borsh")]
+    #[inline]
+    fn to_borsh_hash_into<T: Digest>(&self, hasher: &mut T)
+    where
+        Self: ToBorshBytes + BorshSerialize,
+    {
+        self.to_hash_with_into::<Borsh, T>(hasher)
+    }
+
+    // Compact json (same as `to_json`)
+    #[cfg(feature = "json")]
+    #[inline]
+    fn try_to_json_hash<T: Digest>(&self) -> Result<Output<T>>
+    where
+        Self: ToJson + Serialize,
+    {
+        self.try_to_hash_with::<Json, T>()
+    }
+    #[cfg(feature = "json")]
+    #[inline]
+    fn try_to_json_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
+    where
+        Self: ToJson + Serialize,
+    {
+        self.try_to_hash_with_into::<Json, T>(hasher)
+    }
+    #[cfg(feature = "json")]
+    #[inline]
+    fn to_json_hash<T: Digest>(&self) -> Output<T>
+    where
+        Self: ToJson + Serialize,
+    {
+        self.to_hash_with::<Json, T>()
+    }
+    #[cfg(feature = "json")]
+    #[inline]
+    fn to_json_hash_into<T: Digest>(&self, hasher: &mut T)
     where
-        Self: DeserializeOwned,
+        Self: ToJson + Serialize,
     {
-        match Self::OPTIONS.endian {
-            Endian::Big => Self::try_from_be_bytes_from(reader),
-            Endian::Little => Self::try_from_le_bytes_from(reader),
-            Endian::Native => Self::try_from_ne_bytes_from(reader),
-        }
+        self.to_hash_with_into::<Json, T>(hasher)
     }
-    // --------------------------------------------------
+
+    // Canonical json, object keys sorted
+    #[cfg(feature = "json")]
     #[inline]
-    fn to_bytes(&self) -> Vec<u8>
+    fn try_to_canonical_json_hash<T: Digest>(&self) -> Result<Output<T>>
     where
-        Self: Serialize,
+        Self: ToJson + Serialize,
     {
-        self.try_to_bytes().unwrap()
+        self.try_to_hash_with::<CanonicalJson, T>()
     }
+    #[cfg(feature = "json")]
     #[inline]
-    fn to_bytes_into(&self, writer: impl io::Write)
+    fn try_to_canonical_json_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
     where
-        Self: Serialize,
+        Self: ToJson + Serialize,
     {
-        self.try_to_bytes_into(writer).unwrap()
+        self.try_to_hash_with_into::<CanonicalJson, T>(hasher)
     }
+    #[cfg(feature = "json")]
     #[inline]
-    fn from_bytes(bytes: impl AsBytes) -> Self
+    fn to_canonical_json_hash<T: Digest>(&self) -> Output<T>
     where
-        Self: DeserializeOwned,
+        Self: ToJson + Serialize,
     {
-        Self::try_from_bytes(bytes).unwrap()
+        self.to_hash_with::<CanonicalJson, T>()
     }
+    #[cfg(feature = "json")]
     #[inline]
-    fn from_bytes_from(reader: impl io::Read) -> Self
+    fn to_canonical_json_hash_into<T: Digest>(&self, hasher: &mut T)
     where
-        Self: DeserializeOwned,
+        Self: ToJson + Serialize,
     {
-        Self::try_from_bytes_from(reader).unwrap()
+        self.to_hash_with_into::<CanonicalJson, T>(hasher)
     }
 
-    // ----------------------------------------------------------------------
-}
-
-#[cfg(feature = "bytes")]
-pub trait ToBorshBytes {
-    /// BorshSerialize to bytes.
+    // Yaml (same as `to_yaml`)
+    #[cfg(feature = "yaml")]
     #[inline]
-    fn try_to_borsh_bytes(&self) -> Result<Vec<u8>>
+    fn try_to_yaml_hash<T: Digest>(&self) -> Result<Output<T>>
     where
-        Self: BorshSerialize,
+        Self: ToYaml + Serialize,
     {
-        self.try_to_vec().map_err(Error::Io)
+        self.try_to_hash_with::<Yaml, T>()
     }
+    #[cfg(feature = "yaml")]
     #[inline]
-    fn try_to_borsh_bytes_into(&self, mut writer: impl io::Write) -> Result<()>
+    fn try_to_yaml_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
     where
-        Self: BorshSerialize,
+        Self: ToYaml + Serialize,
     {
-        self.serialize(&mut writer).map_err(Error::Io)
+        self.try_to_hash_with_into::<Yaml, T>(hasher)
     }
-    // ---------------------
+    #[cfg(feature = "yaml")]
     #[inline]
-    fn to_borsh_bytes(&self) -> Vec<u8>
+    fn to_yaml_hash<T: Digest>(&self) -> Output<T>
     where
-        Self: BorshSerialize,
+        Self: ToYaml + Serialize,
     {
-        self.try_to_borsh_bytes().unwrap()
+        self.to_hash_with::<Yaml, T>()
     }
+    #[cfg(feature = "yaml")]
     #[inline]
-    fn to_borsh_bytes_into(&self, writer: impl io::Write)
+    fn to_yaml_hash_into<T: Digest>(&self, hasher: &mut T)
     where
-        Self: BorshSerialize,
+        Self: ToYaml + Serialize,
     {
-        self.try_to_borsh_bytes_into(writer).unwrap()
+        self.to_hash_with_into::<Yaml, T>(hasher)
     }
-    // ---------------------
 
-    /// BorshDeserialize from bytes.
+    // Toml (same as `to_toml`)
+    #[cfg(feature = "toml")]
     #[inline]
-    fn try_from_borsh_bytes(bytes: impl AsBytes) -> Result<Self>
+    fn try_to_toml_hash<T: Digest>(&self) -> Result<Output<T>>
     where
-        Self: BorshDeserialize,
+        Self: ToToml + Serialize,
     {
-        BorshDeserialize::try_from_slice(bytes.as_byte_slice()).map_err(Error::Io)
+        self.try_to_hash_with::<Toml, T>()
     }
+    #[cfg(feature = "toml")]
     #[inline]
-    fn try_from_borsh_bytes_from(mut reader: impl io::Read) -> Result<Self>
+    fn try_to_toml_hash_into<T: Digest>(&self, hasher: &mut T) -> Result<()>
     where
-        Self: BorshDeserialize,
+        Self: ToToml + Serialize,
     {
-        BorshDeserialize::try_from_reader(&mut reader).map_err(Error::Io)
+        self.try_to_hash_with_into::<Toml, T>(hasher)
     }
-    // ---------------------
+    #[cfg(feature = "toml")]
     #[inline]
-    fn from_borsh_bytes(bytes: impl AsBytes) -> Self
+    fn to_toml_hash<T: Digest>(&self) -> Output<T>
     where
-        Self: BorshDeserialize,
+        Self: ToToml + Serialize,
     {
-        Self::try_from_borsh_bytes(bytes).unwrap()
+        self.to_hash_with::<Toml, T>()
     }
+    #[cfg(feature = "toml")]
     #[inline]
-    fn from_borsh_bytes_from(reader: impl io::Read) -> Self
+    fn to_toml_hash_into<T: Digest>(&self, hasher: &mut T)
     where
-        Self: BorshDeserialize,
+        Self: ToToml + Serialize,
     {
-        Self::try_from_borsh_bytes_from(reader).unwrap()
+        self.to_hash_with_into::<Toml, T>(hasher)
     }
 }
 
-#[cfg(feature = "hash")]
-/// # A trait that can hash bytes.
-pub trait ToHash {
+#[cfg(feature = "signature")]
+/// # A trait that can sign and verify the encoding with any `signature` implementation.
+///
+/// It signs the `HashView`, so `#[hash(skip)]` fields (e.g. the signature itself) are not signed.
+pub trait ToSigned {
+    // Bincode
+    #[cfg(feature = "bytes")]
     #[inline]
-    fn try_to_hash<T: Digest + io::Write>(&self) -> Result<Output<T>>
+    fn try_sign<S: Signer<Sig>, Sig>(&self, signer: &S) -> Result<Sig>
     where
-        Self: ToBytes + Serialize,
+        Self: ToHash + ToBytes + Serialize,
     {
-        let mut hasher = T::new();
-        self.try_to_hash_into(&mut hasher)?;
-        Ok(hasher.finalize())
+        Ok(signer.try_sign(&HashView(self).try_to_bytes()?)?)
     }
+    #[cfg(feature = "bytes")]
     #[inline]
-    fn try_to_hash_into<T: Digest + io::Write>(&self, hasher: &mut T) -> Result<()>
+    fn sign<S: Signer<Sig>, Sig>(&self, signer: &S) -> Sig
     where
-        Self: ToBytes + Serialize,
+        Self: ToHash + ToBytes + Serialize,
     {
-        self.try_to_bytes_into(hasher)
+        self.try_sign(signer).unwrap()
     }
+    #[cfg(feature = "bytes")]
     #[inline]
-    fn to_hash<T: Digest + io::Write>(&self) -> Output<T>
+    fn verify<V: Verifier<Sig>, Sig>(&self, verifier: &V, signature: &Sig) -> Result<()>
     where
-        Self: ToBytes + Serialize,
+        Self: ToHash + ToBytes + Serialize,
     {
-        let mut hasher = T::new();
-        self.to_hash_into(&mut hasher);
-        hasher.finalize()
+        Ok(verifier.verify(&HashView(self).try_to_bytes()?, signature)?)
     }
+
+    /// Sign into a `Signed` envelope that carries the verifying key.
+    #[cfg(feature = "bytes")]
     #[inline]
-    fn to_hash_into<T: Digest + io::Write>(&self, hasher: &mut T)
+    fn try_into_signed<S, Sig>(self, signer: &S) -> Result<Signed<Self, Sig, S::VerifyingKey>>
     where
-        Self: ToBytes + Serialize,
+        Self: ToHash + ToBytes + Serialize + Sized,
+        S: Signer<Sig> + Keypair,
+        S::VerifyingKey: Verifier<Sig>,
     {
-        self.to_bytes_into(hasher)
+        Signed::try_new(self, signer)
     }
-
-    // Borsh
+    #[cfg(feature = "bytes")]
     #[inline]
-    fn try_to_borsh_hash<T: Digest + io::Write>(&self) -> Result<Output<T>>
+    fn into_signed<S, Sig>(self, signer: &S) -> Signed<Self, Sig, S::VerifyingKey>
     where
-        Self: ToBorshBytes + BorshSerialize,
+        Self: ToHash + ToBytes + Serialize + Sized,
+        S: Signer<Sig> + Keypair,
+        S::VerifyingKey: Verifier<Sig>,
     {
-        let mut hasher = T::new();
-        self.try_to_borsh_hash_into(&mut hasher)?;
-        Ok(hasher.finalize())
+        self.try_into_signed(signer).unwrap()
     }
+
+    // Borsh
+    #[cfg(feature = "borsh")]
     #[inline]
-    fn try_to_borsh_hash_into<T: Digest + io::Write>(&self, hasher: &mut T) -> Result<()>
+    fn try_sign_borsh<S: Signer<Sig>, Sig>(&self, signer: &S) -> Result<Sig>
     where
-        Self: ToBorshBytes + BorshSerialize,
+        Self: ToHash + ToBorshBytes + BorshSerialize,
     {
-        self.try_to_borsh_bytes_into(hasher)
+        Ok(signer.try_sign(&Borsh::try_encode(&HashView(self))?)?)
     }
+    #[cfg(feature = "borsh")]
     #[inline]
-    fn to_borsh_hash<T: Digest + io::Write>(&self) -> Output<T>
+    fn sign_borsh<S: Signer<Sig>, Sig>(&self, signer: &S) -> Sig
     where
-        Self: ToBorshBytes + BorshSerialize,
+        Self: ToHash + ToBorshBytes + BorshSerialize,
     {
-        let mut hasher = T::new();
-        self.to_borsh_hash_into(&mut hasher);
-        hasher.finalize()
+        self.try_sign_borsh(signer).unwrap()
     }
+    #[cfg(feature = "borsh")]
     #[inline]
-    fn to_borsh_hash_into<T: Digest + io::Write>(&self, hasher: &mut T)
+    fn verify_borsh<V: Verifier<Sig>, Sig>(&self, verifier: &V, signature: &Sig) -> Result<()>
     where
-        Self: ToBorshBytes + BorshSerialize,
+        Self: ToHash + ToBorshBytes + BorshSerialize,
     {
-        self.to_borsh_bytes_into(hasher)
+        Ok(verifier.verify(&Borsh::try_encode(&HashView(self))?, signature)?)
     }
 }
 
@@ -672,6 +1737,52 @@ pub trait ToJson {
     {
         self.try_to_json_pretty_into(writer).unwrap()
     }
+
+    // --- async ---
+    /// Encode json to an async writer.
+    ///
+    /// The value is encoded in memory, then written with `AsyncWriteExt::write_all`.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_json_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Serialize,
+    {
+        write_all_async(writer, self.try_to_json().map(String::into_bytes))
+    }
+    /// The reader is read to the end, then decoded in memory.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_json_async<R: AsyncRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        read_all_async(reader, Self::try_from_json)
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_json_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: Serialize,
+    {
+        unwrap_async(self.try_to_json_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_json_async<R: AsyncRead + Unpin + Send>(reader: R) -> impl Future<Output = Self> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        unwrap_async(Self::try_from_json_async(reader))
+    }
 }
 
 #[cfg(feature = "yaml")]
@@ -766,6 +1877,52 @@ pub trait ToYaml {
     {
         Self::try_from_yaml_from(reader).unwrap()
     }
+
+    // --- async ---
+    /// Encode yaml to an async writer.
+    ///
+    /// The value is encoded in memory, then written with `AsyncWriteExt::write_all`.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_yaml_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Serialize,
+    {
+        write_all_async(writer, self.try_to_yaml().map(String::into_bytes))
+    }
+    /// The reader is read to the end, then decoded in memory.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_yaml_async<R: AsyncRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        read_all_async(reader, Self::try_from_yaml)
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_yaml_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: Serialize,
+    {
+        unwrap_async(self.try_to_yaml_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_yaml_async<R: AsyncRead + Unpin + Send>(reader: R) -> impl Future<Output = Self> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        unwrap_async(Self::try_from_yaml_async(reader))
+    }
 }
 
 #[cfg(feature = "toml")]
@@ -908,6 +2065,52 @@ pub trait ToToml {
     {
         self.try_to_toml_pretty_into(writer).unwrap()
     }
+
+    // --- async ---
+    /// Encode toml to an async writer.
+    ///
+    /// The value is encoded in memory, then written with `AsyncWriteExt::write_all`.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_toml_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Serialize,
+    {
+        write_all_async(writer, self.try_to_toml().map(String::into_bytes))
+    }
+    /// The reader is read to the end, then decoded in memory.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_toml_async<R: AsyncRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        read_all_async(reader, Self::try_from_toml)
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_toml_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send
+    where
+        Self: Serialize,
+    {
+        unwrap_async(self.try_to_toml_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_toml_async<R: AsyncRead + Unpin + Send>(reader: R) -> impl Future<Output = Self> + Send
+    where
+        Self: DeserializeOwned,
+    {
+        unwrap_async(Self::try_from_toml_async(reader))
+    }
 }
 
 #[cfg(feature = "hex")]
@@ -928,6 +2131,29 @@ pub trait ToHex: AsBytes {
         }
         Ok(())
     }
+    /// At most `max_len` hex digits (without `0x`), then `...` when cut.
+    #[inline]
+    fn try_to_hex_into_truncated(
+        &self,
+        mut writer: impl fmt::Write,
+        mode: HexMode,
+        max_len: usize,
+    ) -> Result<()> {
+        let bytes = self.as_byte_slice();
+        if bytes.len() * 2 <= max_len {
+            return self.try_to_hex_into_with_mode(writer, mode);
+        }
+        let mut hex = (&bytes[..max_len.div_ceil(2)]).try_to_hex_with_mode(mode)?;
+        hex.truncate(max_len + if mode.has_0x() { 2 } else { 0 });
+        write!(writer, "{hex}...")?;
+        Ok(())
+    }
+    #[inline]
+    fn try_to_hex_truncated(&self, mode: HexMode, max_len: usize) -> Result<String> {
+        let mut hex = String::new();
+        self.try_to_hex_into_truncated(&mut hex, mode, max_len)?;
+        Ok(hex)
+    }
     #[inline]
     fn try_to_hex_with_mode(&self, mode: HexMode) -> Result<String> {
         let mut hex = String::with_capacity(
@@ -939,7 +2165,7 @@ pub trait ToHex: AsBytes {
     #[inline]
     fn try_from_hex(hex: impl AsBytes) -> Result<Vec<u8>> {
         let mut hex = hex.as_byte_slice();
-        if hex.starts_with(&[b'0', b'x']) {
+        if hex.starts_with(b"0x") {
             hex = &hex[2..];
         }
         if hex.len() % 2 != 0 {
@@ -947,7 +2173,7 @@ pub trait ToHex: AsBytes {
         }
         let mut bytes = Vec::with_capacity(hex.len() / 2);
         for i in (0..hex.len()).step_by(2) {
-            let s = std::str::from_utf8(&hex[i..i + 2])?;
+            let s = str::from_utf8(&hex[i..i + 2])?;
             let byte = u8::from_str_radix(s, 16)?;
             bytes.push(byte);
         }
@@ -960,7 +2186,7 @@ pub trait ToHex: AsBytes {
 
         let mut v = Vec::new();
         let mut take_into_v = |double: &mut [u8; 2]| -> Result<()> {
-            let ch = std::str::from_utf8(double)?;
+            let ch = str::from_utf8(double)?;
             let byte = u8::from_str_radix(ch, 16)?;
             v.push(byte);
             double[0] = 0;
@@ -992,7 +2218,7 @@ pub trait ToHex: AsBytes {
         Self: AsMut<[u8]>,
     {
         let mut hex = hex.as_byte_slice();
-        if hex.starts_with(&[b'0', b'x']) {
+        if hex.starts_with(b"0x") {
             hex = &hex[2..];
         }
         if hex.len() % 2 != 0 {
@@ -1004,7 +2230,7 @@ pub trait ToHex: AsBytes {
             return Err(Error::OutOfBounds(bytes.len(), hex_bytes_len));
         }
         for i in (0..hex.len()).step_by(2) {
-            let s = std::str::from_utf8(&hex[i..i + 2])?;
+            let s = str::from_utf8(&hex[i..i + 2])?;
             let byte = u8::from_str_radix(s, 16)?;
             bytes[i / 2] = byte;
         }
@@ -1092,6 +2318,52 @@ pub trait ToHex: AsBytes {
     {
         self.try_copy_from_hex(hex).unwrap()
     }
+
+    // --- async ---
+    /// Encode hex to an async writer.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_hex_async_with_mode<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+        mode: HexMode,
+    ) -> impl Future<Output = Result<()>> + Send {
+        write_all_async(
+            writer,
+            self.try_to_hex_with_mode(mode).map(String::into_bytes),
+        )
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_to_hex_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = Result<()>> + Send {
+        self.try_to_hex_async_with_mode(writer, HexMode::Lower)
+    }
+    /// The reader is read to the end, then decoded in memory.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_from_hex_async<R: AsyncRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        read_all_async(reader, Self::try_from_hex)
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn to_hex_async<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: W,
+    ) -> impl Future<Output = ()> + Send {
+        unwrap_async(self.try_to_hex_async(writer))
+    }
+    #[cfg(feature = "async")]
+    #[inline]
+    fn from_hex_async<R: AsyncRead + Unpin + Send>(
+        reader: R,
+    ) -> impl Future<Output = Vec<u8>> + Send {
+        unwrap_async(Self::try_from_hex_async(reader))
+    }
 }
 
 #[cfg(feature = "hex")]
@@ -1102,9 +2374,10 @@ impl<T: AsBytes> ToHex for T {}
 mod tests {
     use super::*;
     use serde::Deserialize;
+    use std::io;
 
     use sha2::Sha256;
-    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug)]
     struct Test {
         a: u32,
         b: String,
@@ -1112,6 +2385,7 @@ mod tests {
         d: Vec<u8>,
     }
     impl ToBytes for Test {}
+    impl ToBorshBytes for Test {}
     impl ToHash for Test {}
     impl ToJson for Test {}
     impl ToYaml for Test {}
@@ -1251,6 +2525,301 @@ mod tests {
         assert_eq!(hash, want);
     }
 
+    #[test]
+    fn test_to_hash_with() {
+        use crate::codec::{Bincode, CanonicalJson};
+
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        assert_eq!(
+            test.to_hash_with::<Bincode, Sha256>(),
+            test.to_hash::<Sha256>()
+        );
+        assert_eq!(
+            test.to_hash_with::<CanonicalJson, Sha256>(),
+            Sha256::digest(CanonicalJson::encode(&test))
+        );
+    }
+
+    #[test]
+    fn test_to_hash_with_domain() {
+        #[derive(Serialize)]
+        struct Untagged(u32);
+        impl ToBytes for Untagged {}
+        impl ToHash for Untagged {}
+
+        #[derive(Serialize)]
+        struct Tagged(u32);
+        impl ToBytes for Tagged {}
+        impl ToHash for Tagged {
+            const OPTIONS: ToHashOptions = ToHashOptions {
+                domain: Some("tx:v1"),
+            };
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(5u64.to_le_bytes());
+        hasher.update(b"tx:v1");
+        hasher.update(1u32.to_le_bytes());
+        let want = hasher.finalize();
+
+        assert_eq!(Tagged(1).to_hash::<Sha256>(), want);
+        assert_eq!(Untagged(1).to_hash_with_domain::<Sha256>("tx:v1"), want);
+        assert_eq!(Tagged(1).to_hash_with_domain::<Sha256>("tx:v1"), want);
+        assert_ne!(Untagged(1).to_hash::<Sha256>(), want);
+    }
+
+    #[test]
+    fn test_to_hash_xof() {
+        use sha3::{
+            digest::{ExtendableOutput, Update},
+            Shake256,
+        };
+
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        let long = test.to_hash_xof::<Shake256>(64);
+        assert_eq!(long.len(), 64);
+        assert_eq!(long[..32], test.to_hash_xof::<Shake256>(32));
+
+        let mut hasher = Shake256::default();
+        hasher.update(&test.to_bytes());
+        let mut want = [0; 64];
+        hasher.finalize_xof_into(&mut want);
+        let mut out = [0; 64];
+        test.to_hash_xof_into::<Shake256>(&mut out);
+        assert_eq!(out, want);
+
+        #[cfg(feature = "multihash")]
+        assert_eq!(
+            test.to_hash_xof::<blake3::Hasher>(32),
+            test.to_hash::<blake3::Hasher>().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_to_bytes_and_hash() {
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        let (bytes, hash) = test.to_bytes_and_hash::<Sha256>();
+        assert_eq!(bytes, test.to_bytes());
+        assert_eq!(hash, Sha256::digest(&bytes));
+
+        let read = Test::from_bytes_verified::<Sha256>(&bytes[..], hash);
+        assert_eq!(read, test);
+        assert!(Test::try_from_bytes_verified_hex::<Sha256>(&bytes[..], hash.to_hex()).is_ok());
+        assert!(matches!(
+            Test::try_from_bytes_verified::<Sha256>(&bytes[..], hash.to_hex()),
+            Err(Error::HashMismatch { .. })
+        ));
+
+        let mut tampered = bytes.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(
+            Test::try_from_bytes_verified::<Sha256>(&tampered[..], hash),
+            Err(Error::HashMismatch { .. })
+        ));
+    }
+
+    #[cfg(feature = "algorithms")]
+    #[test]
+    fn test_to_hash_dyn() {
+        #[derive(Serialize)]
+        struct Tagged(u32);
+        impl ToBytes for Tagged {}
+        impl ToHash for Tagged {
+            const OPTIONS: ToHashOptions = ToHashOptions {
+                domain: Some("tx:v1"),
+            };
+        }
+
+        let want = Tagged(1).to_hash::<Sha256>();
+        assert_eq!(Tagged(1).to_hash_dyn(HashAlgorithm::Sha256), want.to_vec());
+        assert_eq!(
+            Tagged(1).to_hash_dyn("blake3".parse().unwrap()),
+            Tagged(1).to_hash::<blake3::Hasher>().to_vec()
+        );
+
+        let mut hasher = Sha256::new();
+        Tagged(1).to_hash_update_into(&mut hasher);
+        assert_eq!(hasher.finalize(), want);
+    }
+
+    #[test]
+    fn test_to_hmac() {
+        use hmac::{Hmac, Mac};
+
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(&test.to_bytes());
+        let want = mac.finalize().into_bytes();
+
+        let tag = test.to_hmac::<Sha256>("key");
+        assert_eq!(tag, want);
+        assert!(test.verify_hmac::<Sha256>("key", tag).is_ok());
+        assert!(test.verify_hmac::<Sha256>("other", tag).is_err());
+        assert!(test.verify_hmac::<Sha256>("key", &tag[..16]).is_err());
+    }
+
+    #[test]
+    fn test_verify_hash() {
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        let hash = test.to_hash::<Sha256>();
+        assert!(test.verify_hash::<Sha256>(hash).is_ok());
+        assert!(test.verify_hash_hex::<Sha256>(hash.to_hex()).is_ok());
+        assert!(test
+            .verify_hash_hex::<Sha256>(hash.to_upper_hex_with_0x())
+            .is_ok());
+        // hex is never guessed from the length
+        assert!(matches!(
+            test.verify_hash::<Sha256>(hash.to_hex()),
+            Err(Error::HashMismatch { .. })
+        ));
+        assert!(matches!(
+            test.verify_hash::<Sha256>(&hash.to_hex()[..32]),
+            Err(Error::HashMismatch { .. })
+        ));
+
+        let other = Test { a: 2, ..test };
+        match other.verify_hash_hex::<Sha256>(hash.to_hex()) {
+            Err(Error::HashMismatch { expected, actual }) => {
+                assert_eq!(expected, hash.to_hex());
+                assert_eq!(actual, other.to_hash::<Sha256>().to_hex());
+            }
+            _ => panic!("expected a hash mismatch"),
+        }
+        assert!(other.verify_hash::<Sha256>(&hash[..16]).is_err());
+        assert!(other.verify_hash_hex::<Sha256>("zz").is_err());
+    }
+
+    #[test]
+    fn test_to_encoding_hash() {
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        assert_eq!(
+            test.to_json_hash::<Sha256>(),
+            Sha256::digest(test.to_json())
+        );
+        // fields are already in key order
+        assert_eq!(
+            test.to_json_hash::<Sha256>(),
+            test.to_canonical_json_hash::<Sha256>()
+        );
+        assert_eq!(
+            test.to_yaml_hash::<Sha256>(),
+            Sha256::digest(test.to_yaml())
+        );
+        assert_eq!(
+            test.to_toml_hash::<Sha256>(),
+            Sha256::digest(test.to_toml())
+        );
+
+        let mut hasher = Sha256::new();
+        test.to_canonical_json_hash_into(&mut hasher);
+        assert_eq!(hasher.finalize(), test.to_canonical_json_hash::<Sha256>());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async() {
+        let test = Test {
+            a: 1,
+            b: "hello".to_owned(),
+            c: [0; 32],
+            d: vec![1, 2, 3],
+        };
+        let mut bytes = Vec::new();
+        test.to_bytes_async(&mut bytes).await;
+        assert_eq!(bytes, test.to_bytes());
+        assert_eq!(Test::from_bytes_async(bytes.as_slice()).await, test);
+
+        // two values back to back, read through a buffer smaller than one value
+        let second = Test {
+            a: 2,
+            b: "world".to_owned(),
+            c: [1; 32],
+            d: vec![4],
+        };
+        let mut both = test.to_bytes();
+        both.extend(second.to_bytes());
+        let mut reader = tokio::io::BufReader::with_capacity(4, both.as_slice());
+        assert_eq!(Test::from_bytes_async(&mut reader).await, test);
+        assert_eq!(Test::from_bytes_async(&mut reader).await, second);
+        assert!(Test::try_from_bytes_async(&mut reader).await.is_err());
+
+        let mut both = test.to_borsh_bytes();
+        both.extend(second.to_borsh_bytes());
+        let mut reader = tokio::io::BufReader::with_capacity(4, both.as_slice());
+        assert_eq!(Test::from_borsh_bytes_async(&mut reader).await, test);
+        assert_eq!(Test::from_borsh_bytes_async(&mut reader).await, second);
+        assert!(Test::try_from_borsh_bytes_async(&mut reader).await.is_err());
+
+        // a long string is not decoded again for every buffer fill
+        let long = Test {
+            a: 3,
+            b: "a".repeat(100_000),
+            c: [2; 32],
+            d: vec![5],
+        };
+        let decodes = core::cell::Cell::new(0);
+        let bytes = long.to_bytes();
+        let reader = tokio::io::BufReader::with_capacity(64, bytes.as_slice());
+        let value = read_one_async(reader, |partial| {
+            decodes.set(decodes.get() + 1);
+            Test::try_from_bytes_from(partial)
+        });
+        assert_eq!(value.await.unwrap(), long);
+        assert!(decodes.get() <= 3, "{} decodes", decodes.get());
+
+        decodes.set(0);
+        let bytes = long.to_borsh_bytes();
+        let reader = tokio::io::BufReader::with_capacity(64, bytes.as_slice());
+        let value = read_one_async(reader, |partial| {
+            decodes.set(decodes.get() + 1);
+            Test::try_from_borsh_bytes_from(partial)
+        });
+        assert_eq!(value.await.unwrap(), long);
+        assert!(decodes.get() <= 3, "{} decodes", decodes.get());
+
+        let mut json = Vec::new();
+        test.to_json_async(&mut json).await;
+        assert_eq!(Test::from_json_async(json.as_slice()).await, test);
+
+        let mut hex = Vec::new();
+        b"hello world".to_hex_async(&mut hex).await;
+        assert_eq!(hex, b"68656c6c6f20776f726c64");
+        assert_eq!(
+            Vec::<u8>::from_hex_async(hex.as_slice()).await,
+            b"hello world"
+        );
+    }
+
     #[test]
     fn test_to_json() {
         let test = Test {
@@ -1355,6 +2924,16 @@ mod tests {
         assert_eq!(hex, "0x68656C6C6F20776F726C64");
     }
 
+    #[test]
+    fn test_to_hex_truncated() {
+        let bytes = b"hello world";
+        let hex = |mode, max_len| bytes.try_to_hex_truncated(mode, max_len).unwrap();
+        assert_eq!(hex(HexMode::Lower, 8), "68656c6c...");
+        assert_eq!(hex(HexMode::Upper0x, 5), "0x68656...");
+        assert_eq!(hex(HexMode::Lower0x, 22), "0x68656c6c6f20776f726c64");
+        assert_eq!(hex(HexMode::Lower, 0), "...");
+    }
+
     #[test]
     fn test_from_hex() {
         let bytes = b"hello world";
@@ -59,3 +59,26 @@ impl ToBytesOptions {
         syn::parse_str(&format!("stdto::ToBytesOptions {{ endian: {endian} }}")).unwrap()
     }
 }
+
+// `schema` is opt-in, borsh 0.10 has no schema for `BTreeMap` and friends
+#[derive(Default)]
+pub struct ToBorshBytesOptions {
+    pub schema: bool,
+}
+impl TryFrom<AttributeArgs> for ToBorshBytesOptions {
+    type Error = Error;
+    fn try_from(args: AttributeArgs) -> Result<Self, Self::Error> {
+        let mut options = ToBorshBytesOptions::default();
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("schema") => {
+                    options.schema = true;
+                }
+                _ => {
+                    return Err(Error::new_spanned(arg, "expected `schema`"));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
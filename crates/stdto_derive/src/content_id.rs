@@ -0,0 +1,89 @@
+use super::*;
+use syn::{LitInt, LitStr};
+
+/// `#[stdto::id(...)]`
+#[derive(StructMeta)]
+pub struct IdArgs {
+    hasher: Expr,
+    format: Option<LitStr>,
+    len: Option<LitInt>,
+    namespace: Option<Expr>,
+}
+
+impl IdArgs {
+    /// The return type and the body of `try_content_id`.
+    pub fn to_body(&self) -> Result<(TokenStream2, TokenStream2), Error> {
+        let hasher = &self.hasher;
+        let hash = quote!(stdto::ToHash::try_to_hash::<#hasher>(self)?);
+        let len = match &self.len {
+            Some(len) => quote!(#len),
+            None => quote!(7usize),
+        };
+        let format = self
+            .format
+            .as_ref()
+            .map_or("hex".to_string(), |format| format.value().to_lowercase());
+        if let (Some(len), false) = (&self.len, matches!(format.as_str(), "hex" | "base32")) {
+            return Err(Error::new_spanned(
+                len,
+                "`len` is only for `hex` and `base32`",
+            ));
+        }
+        if let (Some(namespace), false) = (&self.namespace, format == "uuid_v5") {
+            return Err(Error::new_spanned(
+                namespace,
+                "`namespace` is only for `uuid_v5`",
+            ));
+        }
+        Ok(match format.as_str() {
+            "hex" => (
+                quote!(std::string::String),
+                quote!(Ok(stdto::id::short_hex(#hash, #len))),
+            ),
+            "base32" => (
+                quote!(std::string::String),
+                quote!(Ok(stdto::id::short_base32(#hash, #len))),
+            ),
+            "uuid" | "uuid_v8" => (quote!(stdto::uuid::Uuid), quote!(stdto::id::uuid_v8(#hash))),
+            "uuid_v5" => {
+                let namespace = match &self.namespace {
+                    Some(namespace) => quote!(#namespace),
+                    None => quote!(stdto::uuid::Uuid::NAMESPACE_OID),
+                };
+                (
+                    quote!(stdto::uuid::Uuid),
+                    quote!(Ok(stdto::id::uuid_v5(&#namespace, #hash))),
+                )
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &self.format,
+                    "expected `hex`, `base32`, `uuid`, `uuid_v8` or `uuid_v5`",
+                ))
+            }
+        })
+    }
+}
+
+pub fn impl_content_id(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(item as DeriveInput);
+    let args = parse_macro_input!(attr as IdArgs);
+    let (ty, body) = unwrap_error!(args.to_body());
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    quote! {
+        #ast
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The id derived from `to_hash`.
+            #[inline]
+            pub fn try_content_id(&self) -> stdto::error::Result<#ty> {
+                #body
+            }
+            #[inline]
+            pub fn content_id(&self) -> #ty {
+                self.try_content_id().unwrap()
+            }
+        }
+    }
+    .into()
+}
@@ -0,0 +1,93 @@
+use super::*;
+use syn::{LitInt, LitStr};
+
+/// `#[debug(...)]` or `#[display(...)]`
+#[derive(StructMeta, Default)]
+pub struct FormatArgs {
+    hasher: Option<Expr>,
+    mode: Option<LitStr>,
+    max_len: Option<LitInt>,
+}
+
+impl FormatArgs {
+    pub fn take_from(ast: &DeriveInput, attr_name: &str) -> Result<Self, Error> {
+        let mut args = FormatArgs::default();
+        for attr in &ast.attrs {
+            if attr.path.is_ident(attr_name) {
+                args = attr.parse_args::<FormatArgs>()?;
+            }
+        }
+        Ok(args)
+    }
+
+    fn mode(&self) -> Result<TokenStream2, Error> {
+        let Some(mode) = &self.mode else {
+            return Ok(quote!(stdto::HexMode::Upper0x));
+        };
+        Ok(match mode.value().to_lowercase().as_str() {
+            "lower" => quote!(stdto::HexMode::Lower),
+            "upper" => quote!(stdto::HexMode::Upper),
+            "lower0x" => quote!(stdto::HexMode::Lower0x),
+            "upper0x" => quote!(stdto::HexMode::Upper0x),
+            _ => {
+                return Err(Error::new_spanned(
+                    mode,
+                    "expected `lower`, `upper`, `lower0x` or `upper0x`",
+                ))
+            }
+        })
+    }
+
+    /// The body of `fmt`, writing the hex of `bytes` or of its hash.
+    pub fn to_body(
+        &self,
+        ast: &DeriveInput,
+        attr_name: &str,
+        hash: bool,
+    ) -> Result<TokenStream2, Error> {
+        let bytes = match (&self.hasher, hash) {
+            (Some(hasher), true) => quote!(stdto::ToHash::to_hash::<#hasher>(self)),
+            (None, true) => {
+                let message = format!("missing #[{attr_name}(hasher = ...)]");
+                return Err(Error::new_spanned(ast, message));
+            }
+            (Some(hasher), false) => {
+                return Err(Error::new_spanned(
+                    hasher,
+                    "`hasher` is only for DebugHash and DisplayHash",
+                ));
+            }
+            (None, false) => quote!(stdto::ToBytes::to_bytes(self)),
+        };
+        let mode = self.mode()?;
+        let write = match &self.max_len {
+            Some(max_len) => quote!(try_to_hex_into_truncated(f, #mode, #max_len)),
+            None => quote!(try_to_hex_into_with_mode(f, #mode)),
+        };
+        Ok(quote! {
+            use stdto::ToHex;
+            #bytes.#write.map_err(|_| core::fmt::Error)
+        })
+    }
+}
+
+pub fn impl_format(
+    input: TokenStream,
+    fmt_trait: TokenStream2,
+    attr_name: &str,
+    hash: bool,
+) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let args = unwrap_error!(FormatArgs::take_from(&ast, attr_name));
+    let body = unwrap_error!(args.to_body(&ast, attr_name, hash));
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #fmt_trait for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                #body
+            }
+        }
+    }
+    .into()
+}
@@ -0,0 +1,290 @@
+use super::*;
+
+#[derive(Default)]
+pub struct ToHashOptions {
+    pub domain: Option<String>,
+}
+impl TryFrom<AttributeArgs> for ToHashOptions {
+    type Error = Error;
+    fn try_from(args: AttributeArgs) -> Result<Self, Self::Error> {
+        let mut options = ToHashOptions::default();
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(lit),
+                    ..
+                })) => {
+                    if path.is_ident("domain") {
+                        options.domain = Some(lit.value());
+                    } else {
+                        return Err(Error::new_spanned(
+                            path,
+                            "unknown option, expected `domain`",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(Error::new_spanned(arg, "expected `domain = \"...\"`"));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+impl ToHashOptions {
+    pub fn to_expr(&self) -> Expr {
+        let domain = match &self.domain {
+            Some(domain) => quote!(Some(#domain)),
+            None => quote!(None),
+        };
+        parse_quote!(stdto::ToHashOptions { domain: #domain })
+    }
+}
+
+/// Fields selected by `#[hash(skip)]` / `#[hash(only)]`.
+pub enum HashFields {
+    All,
+    Skip(Vec<usize>),
+    Only(Vec<usize>),
+}
+impl HashFields {
+    /// Read and remove the container `#[hash(borsh)]`, the opt-in to borsh hashing of the view.
+    pub fn take_borsh(ast: &mut DeriveInput) -> Result<bool, Error> {
+        let mut borsh = false;
+        let mut error = None;
+        ast.attrs.retain(|attr| {
+            if !attr.path.is_ident("hash") {
+                return true;
+            }
+            match attr.parse_args::<syn::Ident>() {
+                Ok(ident) if ident == "borsh" => borsh = true,
+                _ => error = Some(Error::new_spanned(attr, "expected `#[hash(borsh)]`")),
+            }
+            false
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(borsh),
+        }
+    }
+
+    /// Read and remove the `#[hash(...)]` field attributes.
+    pub fn take_from(ast: &mut DeriveInput) -> Result<Self, Error> {
+        let syn::Data::Struct(data) = &mut ast.data else {
+            for attr in all_field_attrs(ast) {
+                if attr.path.is_ident("hash") {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "`#[hash(...)]` is only supported on struct fields",
+                    ));
+                }
+            }
+            return Ok(HashFields::All);
+        };
+        let (mut skip, mut only) = (Vec::new(), Vec::new());
+        for (i, field) in data.fields.iter_mut().enumerate() {
+            let mut error = None;
+            field.attrs.retain(|attr| {
+                if !attr.path.is_ident("hash") {
+                    return true;
+                }
+                match attr.parse_args::<syn::Ident>() {
+                    Ok(ident) if ident == "skip" => skip.push(i),
+                    Ok(ident) if ident == "only" => only.push(i),
+                    _ => {
+                        error = Some(Error::new_spanned(attr, "expected `skip` or `only`"));
+                    }
+                }
+                false
+            });
+            if let Some(error) = error {
+                return Err(error);
+            }
+        }
+        match (skip.is_empty(), only.is_empty()) {
+            (true, true) => Ok(HashFields::All),
+            (false, true) => Ok(HashFields::Skip(skip)),
+            (true, false) => Ok(HashFields::Only(only)),
+            (false, false) => Err(Error::new_spanned(
+                &ast.ident,
+                "`#[hash(skip)]` and `#[hash(only)]` cannot be mixed",
+            )),
+        }
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        match self {
+            HashFields::All => true,
+            HashFields::Skip(skip) => !skip.contains(&i),
+            HashFields::Only(only) => only.contains(&i),
+        }
+    }
+
+    /// `serialize_hash_view` and `borsh_serialize_hash_view` over the selected fields.
+    pub fn to_method(&self, ast: &DeriveInput, borsh: bool) -> Result<TokenStream2, Error> {
+        let syn::Data::Struct(data) = &ast.data else {
+            return Ok(quote!());
+        };
+        if let HashFields::All = self {
+            return Ok(quote!());
+        }
+        if borsh {
+            if let Some(param) = ast.generics.type_params().next() {
+                return Err(Error::new_spanned(
+                    param,
+                    "`#[hash(borsh)]` needs a struct without type parameters",
+                ));
+            }
+        }
+        let name = &ast.ident;
+        let (_, ty_generics, _) = ast.generics.split_for_impl();
+        let mut generics = ast.generics.clone();
+        generics.params.insert(0, parse_quote!('__stdto));
+        let (view_impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let fields = data
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.contains(*i));
+        let view_fields = fields.clone().map(|(_, field)| {
+            let ty = &field.ty;
+            let serde = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("serde"));
+            match &field.ident {
+                Some(ident) => quote!(#(#serde)* #ident: &'__stdto #ty,),
+                None => quote!(#(#serde)* &'__stdto #ty,),
+            }
+        });
+        let members = fields
+            .clone()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => quote!(#ident),
+                None => {
+                    let index = syn::Index::from(i);
+                    quote!(#index)
+                }
+            })
+            .collect::<Vec<_>>();
+        let values = fields
+            .zip(&members)
+            .map(|((_, field), member)| match &field.ident {
+                Some(_) => quote!(#member: &self.#member,),
+                None => quote!(&self.#member,),
+            });
+        let marker = quote!(core::marker::PhantomData<&'__stdto #name #ty_generics>);
+        let (view, value) = match &data.fields {
+            syn::Fields::Named(_) => (
+                quote!({ #(#view_fields)* #[serde(skip)] __marker: #marker }),
+                quote!(__HashView { #(#values)* __marker: core::marker::PhantomData }),
+            ),
+            _ => (
+                quote!(( #(#view_fields)* #[serde(skip)] #marker ) #where_clause;),
+                quote!(__HashView( #(#values)* core::marker::PhantomData )),
+            ),
+        };
+        let view = match &data.fields {
+            syn::Fields::Named(_) => {
+                quote!(struct __HashView #view_impl_generics #where_clause #view)
+            }
+            _ => quote!(struct __HashView #view_impl_generics #view),
+        };
+        let mut container = serde_container_attrs(&ast.attrs);
+        if !has_serde_key(&container, "rename") {
+            let name = name.to_string();
+            container.push(parse_quote!(#[serde(rename = #name)]));
+        }
+
+        let borsh = if borsh {
+            quote!(#(stdto::borsh::BorshSerialize::serialize(&self.#members, writer)?;)* Ok(()))
+        } else {
+            quote! {
+                struct Unsupported<W>(W);
+                impl<W> Unsupported<W> {
+                    const ERROR: () = panic!(
+                        "borsh hashing with `#[hash(skip)]` or `#[hash(only)]` needs `#[hash(borsh)]`"
+                    );
+                }
+                let () = Unsupported::<W>::ERROR;
+                let _ = (self, writer);
+                Ok(())
+            }
+        };
+
+        Ok(quote! {
+            fn serialize_hash_view<S: stdto::serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                #[derive(stdto::serde::Serialize)]
+                #[serde(crate = "stdto::serde")]
+                #(#container)*
+                #view
+                stdto::serde::Serialize::serialize(&#value, serializer)
+            }
+            fn borsh_serialize_hash_view<W: stdto::borsh::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> stdto::borsh::maybestd::io::Result<()> {
+                #borsh
+            }
+        })
+    }
+}
+
+/// The container `#[serde(...)]` attributes that also apply to the view.
+///
+/// `crate` is set by the view itself, `into` and `remote` name the original type.
+fn serde_container_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => {
+                let kept = list
+                    .nested
+                    .into_iter()
+                    .filter(|nested| match nested {
+                        NestedMeta::Meta(meta) => !["crate", "into", "remote"]
+                            .iter()
+                            .any(|key| meta.path().is_ident(key)),
+                        NestedMeta::Lit(_) => true,
+                    })
+                    .collect::<Vec<_>>();
+                (!kept.is_empty()).then(|| parse_quote!(#[serde(#(#kept),*)]))
+            }
+            _ => Some(attr.clone()),
+        })
+        .collect()
+}
+
+fn has_serde_key(attrs: &[Attribute], key: &str) -> bool {
+    attrs.iter().any(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+            NestedMeta::Meta(meta) => meta.path().is_ident(key),
+            NestedMeta::Lit(_) => false,
+        }),
+        _ => false,
+    })
+}
+
+fn all_field_attrs(ast: &DeriveInput) -> Vec<&Attribute> {
+    match &ast.data {
+        syn::Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .flat_map(|field| field.attrs.iter())
+            .collect(),
+        syn::Data::Union(data) => data
+            .fields
+            .named
+            .iter()
+            .flat_map(|field| field.attrs.iter())
+            .collect(),
+        syn::Data::Struct(_) => Vec::new(),
+    }
+}
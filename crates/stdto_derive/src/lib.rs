@@ -5,6 +5,15 @@ use cmn::*;
 mod bytes;
 use bytes::*;
 
+mod content_id;
+use content_id::*;
+
+mod format;
+use format::*;
+
+mod hash;
+use hash::*;
+
 #[proc_macro_attribute]
 pub fn serde(_: TokenStream, item: TokenStream) -> TokenStream {
     let mut ast = syn::parse_macro_input!(item as syn::DeriveInput);
@@ -105,8 +114,13 @@ pub fn to_borsh_bytes(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn borsh_bytes(_: TokenStream, item: TokenStream) -> TokenStream {
-    let item = impl_attribute_with_borsh(item, None);
+pub fn borsh_bytes(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AttributeArgs);
+    let options = unwrap_error!(ToBorshBytesOptions::try_from(attr));
+    let schema = options
+        .schema
+        .then(|| parse_quote!(#[derive(stdto::borsh::BorshSchema)]));
+    let item = impl_attribute_with_borsh(item, schema);
     let ast = parse_macro_input!(item as DeriveInput);
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
@@ -117,24 +131,55 @@ pub fn borsh_bytes(_: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
-#[proc_macro_derive(ToHash)]
+#[proc_macro_derive(ToHash, attributes(hash))]
 pub fn to_hash(input: TokenStream) -> TokenStream {
-    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let mut ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let borsh = unwrap_error!(HashFields::take_borsh(&mut ast));
+    let fields = unwrap_error!(HashFields::take_from(&mut ast));
+    let method = unwrap_error!(fields.to_method(&ast, borsh));
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
     quote! {
         impl #impl_generics stdto::ToHash for #name #ty_generics #where_clause {
+            #method
         }
     }
     .into()
 }
 
 #[proc_macro_attribute]
-pub fn hash(_: TokenStream, item: TokenStream) -> TokenStream {
-    let ast = parse_macro_input!(item as syn::DeriveInput);
+pub fn hash(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut ast = parse_macro_input!(item as DeriveInput);
+    let attr = parse_macro_input!(attr as AttributeArgs);
+    let options = unwrap_error!(ToHashOptions::try_from(attr)).to_expr();
+    let borsh = unwrap_error!(HashFields::take_borsh(&mut ast));
+    let fields = unwrap_error!(HashFields::take_from(&mut ast));
+    let method = unwrap_error!(fields.to_method(&ast, borsh));
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
     quote! {
-         #[derive(stdto::ToHash)]
-         #ast
+        #ast
+        impl #impl_generics stdto::ToHash for #name #ty_generics #where_clause {
+            const OPTIONS: stdto::ToHashOptions = #options;
+            #method
+        }
+    }
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn id(attr: TokenStream, item: TokenStream) -> TokenStream {
+    impl_content_id(attr, item)
+}
+
+#[proc_macro_derive(ToSigned)]
+pub fn to_signed(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    quote! {
+        impl #impl_generics stdto::ToSigned for #name #ty_generics #where_clause {
+        }
     }
     .into()
 }
@@ -190,51 +235,73 @@ pub fn debug_bytes(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(DebugHex)]
-pub fn debug_hex(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(BytesEq)]
+pub fn bytes_eq(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
     quote! {
-        impl #impl_generics core::fmt::Debug for #name #ty_generics #where_clause {
-            fn fmt(&self, mut f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                use stdto::ToHex;
-                self.to_bytes().try_to_upper_hex_into_with_0x(&mut f).map_err(|_| core::fmt::Error)
+        impl #impl_generics core::cmp::PartialEq for #name #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                stdto::ToBytes::to_bytes(self) == stdto::ToBytes::to_bytes(other)
             }
         }
+        impl #impl_generics core::cmp::Eq for #name #ty_generics #where_clause {}
     }
     .into()
 }
 
-#[derive(StructMeta)]
-struct Debug {
-    hasher: Expr,
-    // mode: Option<Expr>,
-}
-#[proc_macro_derive(DebugHash, attributes(debug))]
-pub fn debug_hash(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(BytesHash)]
+pub fn bytes_hash(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
-    let mut args = None;
-    for attr in &ast.attrs {
-        if attr.path.is_ident("debug") {
-            args = Some(unwrap_error!(attr.parse_args::<Debug>()));
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    quote! {
+        impl #impl_generics core::hash::Hash for #name #ty_generics #where_clause {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                state.write(&stdto::ToBytes::to_bytes(self))
+            }
         }
     }
-    let Some(args) = args else {
-        return Error::new_spanned(ast, "missing #[debug(hasher = ...)]")
-            .to_compile_error()
-            .into();
-        };
+    .into()
+}
 
+#[proc_macro_derive(BytesOrd)]
+pub fn bytes_ord(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
-    let hasher = args.hasher;
     quote! {
-        impl #impl_generics core::fmt::Debug for #name #ty_generics #where_clause {
-            fn fmt(&self, mut f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                write!(f, "0x{:02X}", self.to_hash::<#hasher>())
+        impl #impl_generics core::cmp::PartialOrd for #name #ty_generics #where_clause {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(core::cmp::Ord::cmp(self, other))
+            }
+        }
+        impl #impl_generics core::cmp::Ord for #name #ty_generics #where_clause {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                stdto::ToBytes::to_bytes(self).cmp(&stdto::ToBytes::to_bytes(other))
             }
         }
     }
     .into()
 }
+
+#[proc_macro_derive(DebugHex, attributes(debug))]
+pub fn debug_hex(input: TokenStream) -> TokenStream {
+    impl_format(input, quote!(core::fmt::Debug), "debug", false)
+}
+
+#[proc_macro_derive(DebugHash, attributes(debug))]
+pub fn debug_hash(input: TokenStream) -> TokenStream {
+    impl_format(input, quote!(core::fmt::Debug), "debug", true)
+}
+
+#[proc_macro_derive(DisplayHex, attributes(display))]
+pub fn display_hex(input: TokenStream) -> TokenStream {
+    impl_format(input, quote!(core::fmt::Display), "display", false)
+}
+
+#[proc_macro_derive(DisplayHash, attributes(display))]
+pub fn display_hash(input: TokenStream) -> TokenStream {
+    impl_format(input, quote!(core::fmt::Display), "display", true)
+}
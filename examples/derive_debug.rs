@@ -43,6 +43,16 @@ struct Test3 {
     e: BTreeMap<u8, f64>,
 }
 
+#[stdto::bytes]
+#[stdto::hash]
+#[derive(DebugHash, stdto::DisplayHex)]
+#[debug(hasher = sha2::Sha256, mode = "lower0x", max_len = 8)]
+#[display(mode = "lower", max_len = 16)]
+struct Test4 {
+    a: u32,
+    b: String,
+}
+
 fn main() {
     let origin = Test {
         a: 1,
@@ -76,4 +86,14 @@ fn main() {
         debuged,
         "0x5E55DEC762F7AD8B7FF8FD0965043E77FC24DE4F04D49DAE42C76E67583B59E3"
     );
+
+    let origin4 = Test4 {
+        a: 1,
+        b: "test".to_string(),
+    };
+    assert_eq!(
+        format!("{:?}", origin4),
+        format!("0x{}...", &origin4.to_hash::<sha2::Sha256>().to_hex()[..8])
+    );
+    assert_eq!(format!("{}", origin4), "0100000004000000...");
 }
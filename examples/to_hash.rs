@@ -5,6 +5,7 @@ use stdto::prelude::*;
 
 #[stdto::bytes]
 #[stdto::borsh_bytes]
+#[stdto::json]
 #[stdto::hash]
 struct Test {
     a: u32,
@@ -46,5 +47,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     origin.to_borsh_hash_into(&mut hasher);
     let hash: [u8; 32] = hasher.finalize().into();
     assert_eq!(hash, want);
+
+    // hash the canonical json form
+    let hash = origin.to_canonical_json_hash::<sha2::Sha256>();
+    assert_eq!(hash, sha2::Sha256::digest(origin.to_json()));
     Ok(())
 }
@@ -1,20 +1,32 @@
-pub use stdto_core::error;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use stdto_core::{codec, error, io};
 
 // ----------------------------------------------------
 
 /// core and derive traits.
 pub mod prelude {
-    pub use stdto_core::{AsBytes, ToStringForBytes};
+    pub use stdto_core::{AsBytes, BinaryCodec, ToStringForBytes};
+
+    #[cfg(feature = "borsh")]
+    pub use stdto_core::{borsh, ToBorshBytes};
+    #[cfg(all(feature = "borsh", feature = "derive"))]
+    pub use stdto_derive::ToBorshBytes;
 
     #[cfg(feature = "bytes")]
-    pub use stdto_core::{borsh, ToBorshBytes, ToBytes};
+    pub use stdto_core::ToBytes;
     #[cfg(all(feature = "bytes", feature = "derive"))]
-    pub use stdto_derive::{DebugBytes, ToBorshBytes, ToBytes};
+    pub use stdto_derive::{BytesEq, BytesHash, BytesOrd, DebugBytes, ToBytes};
 
     #[cfg(feature = "hash")]
     pub use stdto_core::ToHash;
     #[cfg(all(feature = "hash", feature = "derive"))]
-    pub use stdto_derive::{DebugHash, ToHash};
+    pub use stdto_derive::{DebugHash, DisplayHash, ToHash};
+
+    #[cfg(feature = "signature")]
+    pub use stdto_core::ToSigned;
+    #[cfg(all(feature = "signature", feature = "derive"))]
+    pub use stdto_derive::ToSigned;
 
     #[cfg(feature = "json")]
     pub use stdto_core::ToJson;
@@ -34,7 +46,7 @@ pub mod prelude {
     #[cfg(feature = "hex")]
     pub use stdto_core::ToHex;
     #[cfg(all(feature = "hex", feature = "derive"))]
-    pub use stdto_derive::DebugHex;
+    pub use stdto_derive::{DebugHex, DisplayHex};
 }
 pub use prelude::*;
 
@@ -47,17 +59,48 @@ pub use stdto_derive::serde;
 
 // -----------------------------------------------------
 
+#[cfg(feature = "borsh")]
+pub use stdto_core::borsh;
+#[cfg(all(feature = "borsh", feature = "json"))]
+pub use stdto_core::{borsh_dump, borsh_schema_to_json, schema, try_borsh_dump};
+#[cfg(all(feature = "borsh", feature = "derive"))]
+pub use stdto_derive::{borsh, borsh_bytes};
+
 #[cfg(feature = "bytes")]
-pub use stdto_core::{borsh, Endian, ToBytesOptions};
+pub use stdto_core::{explain, ByteSpan, BytesLayout, Endian, ToBytesOptions};
 #[cfg(all(feature = "bytes", feature = "derive"))]
-pub use stdto_derive::{borsh, borsh_bytes, bytes};
+pub use stdto_derive::bytes;
 
 // -----------------------------------------------------
 
 #[cfg(feature = "hash")]
-pub use stdto_core::digest;
+pub use stdto_core::{digest, hash_reader, try_hash_reader, Hash, HashView, ToHashOptions};
+#[cfg(all(feature = "hash", feature = "std"))]
+pub use stdto_core::{hash_file, try_hash_file};
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub use stdto_core::{
+    chain, hash_seq, hash_set, merkle, try_hash_seq, try_hash_set, Hashed, HashedMut,
+};
 #[cfg(all(feature = "hash", feature = "derive"))]
-pub use stdto_derive::hash;
+pub use stdto_derive::{hash, id};
+#[cfg(feature = "hash")]
+pub use stdto_core::id;
+#[cfg(feature = "uuid")]
+pub use stdto_core::uuid;
+
+#[cfg(all(feature = "hash", feature = "bytes"))]
+pub use stdto_core::{dir, hash_dir, try_hash_dir};
+#[cfg(feature = "multihash")]
+pub use stdto_core::multihash;
+#[cfg(feature = "algorithms")]
+pub use stdto_core::HashAlgorithm;
+
+// -----------------------------------------------------
+
+#[cfg(feature = "signature")]
+pub use stdto_core::signature;
+#[cfg(all(feature = "signature", feature = "bytes"))]
+pub use stdto_core::Signed;
 
 // -----------------------------------------------------
 
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use stdto::prelude::*;
+
+#[stdto::borsh_bytes(schema)]
+#[derive(Debug, PartialEq, Eq)]
+struct Test {
+    a: u32,
+    b: String,
+    c: [u8; 4],
+    d: Vec<u8>,
+    e: HashMap<u8, u64>,
+}
+
+#[test]
+fn borsh_dump() {
+    let origin = Test {
+        a: 1,
+        b: "test".to_string(),
+        c: [5; 4],
+        d: vec![3; 2],
+        e: HashMap::from([(1, 2)]),
+    };
+    let bytes = origin.to_borsh_bytes();
+    assert_eq!(origin, Test::from_borsh_bytes(&bytes));
+
+    let dumped = Test::borsh_dump(&bytes);
+    assert_eq!(
+        dumped,
+        stdto::serde_json::json!({
+            "a": 1,
+            "b": "test",
+            "c": [5, 5, 5, 5],
+            "d": [3, 3],
+            "e": [[1, 2]],
+        })
+    );
+    assert_eq!(dumped, stdto::borsh_dump(&Test::borsh_schema(), &bytes));
+
+    let schema = Test::borsh_schema_json();
+    assert_eq!(schema["declaration"], "Test");
+    assert_eq!(
+        schema["definitions"]["Test"]["Struct"]["fields"]["NamedFields"][0],
+        stdto::serde_json::json!(["a", "u32"])
+    );
+}
@@ -46,3 +46,89 @@ fn to_bytes() {
 
     assert_eq_struct(&new1, &new2);
 }
+
+#[test]
+fn explain_bytes() {
+    let origin: Test<String> = Test {
+        a: 1,
+        b: "test".to_string(),
+        c: [0; 32],
+        d: vec![0; 32],
+        e: HashMap::from([(2, "x".to_string())]),
+    };
+
+    let layout = origin.explain_bytes();
+    assert_eq!(layout.bytes, origin.to_bytes());
+
+    let paths = layout
+        .spans
+        .iter()
+        .map(|span| span.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["a", "b", "b", "c", "d", "d", "e", "e.[0].key", "e.[0].value", "e.[0].value"]
+    );
+    assert_eq!(
+        layout.to_string(),
+        concat!(
+            "000000  01 00 00 00                                      a            u32      1\n",
+            "000004  04 00 00 00 00 00 00 00                          b            len      4\n",
+            "00000c  74 65 73 74                                      b            str      \"test\"\n",
+            "000010  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  c            u8 x 32\n",
+            "000020  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00\n",
+            "000030  20 00 00 00 00 00 00 00                          d            len      32\n",
+            "000038  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  d            [u8]\n",
+            "000048  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00\n",
+            "000058  01 00 00 00 00 00 00 00                          e            len      1\n",
+            "000060  02 00 00 00                                      e.[0].key    u32      2\n",
+            "000064  01 00 00 00 00 00 00 00                          e.[0].value  len      1\n",
+            "00006c  78                                               e.[0].value  str      \"x\"\n",
+        )
+    );
+}
+
+#[stdto::bytes]
+#[derive(Debug, stdto::BytesEq, stdto::BytesHash, stdto::BytesOrd)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[test]
+fn bytes_eq_hash_ord() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let a = Point { x: 1.5, y: -0.0 };
+    let b = Point { x: 1.5, y: 0.0 };
+    assert_eq!(a, Point::from_bytes(a.to_bytes()));
+    assert_ne!(a, b);
+
+    let set = HashSet::from([a, b, Point { x: 1.5, y: 0.0 }]);
+    assert_eq!(set.len(), 2);
+
+    let set = set.into_iter().collect::<BTreeSet<_>>();
+    let bytes = set.iter().map(ToBytes::to_bytes).collect::<Vec<_>>();
+    assert!(bytes.windows(2).all(|w| w[0] < w[1]));
+
+    // the bytes are hashed as one slice, not in the chunks the serializer writes
+    #[derive(Default)]
+    struct Writes(Vec<Vec<u8>>);
+    impl std::hash::Hasher for Writes {
+        fn finish(&self) -> u64 {
+            0
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.push(bytes.to_vec());
+        }
+    }
+    let mut writes = Writes::default();
+    let point = Point { x: 1.5, y: 2.0 };
+    std::hash::Hash::hash(&point, &mut writes);
+    assert_eq!(writes.0, [point.to_bytes()]);
+
+    let nan = Point { x: f64::NAN, y: 0.0 };
+    assert_eq!(nan, Point::from_bytes(nan.to_bytes()));
+    assert_eq!(nan.to_u64_hash(), Point::from_bytes(nan.to_bytes()).to_u64_hash());
+    assert_ne!(nan.to_u64_hash(), Point { x: 1.5, y: 0.0 }.to_u64_hash());
+}
@@ -1 +1,255 @@
 //! refer to the [`stdto_core`] crate, traits.rs unit test.
+
+use sha2::{Digest, Sha256};
+use stdto::prelude::*;
+
+#[stdto::bytes]
+#[stdto::borsh_bytes]
+#[stdto::hash(domain = "tx:v1")]
+#[derive(DebugHash)]
+#[debug(hasher = sha2::Sha256)]
+struct Tx {
+    a: u32,
+}
+
+#[test]
+fn hash_with_domain() {
+    let tx = Tx { a: 1 };
+
+    let mut hasher = Sha256::new();
+    hasher.update(5u64.to_le_bytes());
+    hasher.update(b"tx:v1");
+    hasher.update(1u32.to_le_bytes());
+    let want = hasher.finalize();
+
+    assert_eq!(tx.to_hash::<Sha256>(), want);
+    assert_eq!(tx.to_borsh_hash::<Sha256>(), want);
+    assert_eq!(tx.to_hash_with_domain::<Sha256>("tx:v1"), want);
+    assert_eq!(format!("{tx:?}"), format!("0x{want:02X}"));
+}
+
+#[test]
+fn merkle_proof() {
+    use stdto::merkle::{MerkleProof, MerkleTree};
+
+    let tree = (0..5).map(|a| Tx { a }).collect::<MerkleTree<Sha256>>();
+
+    let json = tree.proof(3).unwrap().to_json();
+    let proof = MerkleProof::from_json(json);
+    assert!(proof.verify::<Sha256, _>(tree.root(), &Tx { a: 3 }).is_ok());
+    assert!(proof
+        .verify::<Sha256, _>(tree.root(), &Tx { a: 2 })
+        .is_err());
+}
+
+#[stdto::bytes]
+#[stdto::borsh_bytes]
+#[stdto::json]
+#[stdto::hash]
+#[hash(borsh)]
+struct Signed {
+    a: u32,
+    b: String,
+    #[hash(skip)]
+    signature: Vec<u8>,
+}
+
+#[stdto::bytes]
+#[stdto::hash]
+struct Cached<T>
+where
+    T: stdto::serde::Serialize,
+{
+    #[hash(only)]
+    a: T,
+    #[hash(only)]
+    b: String,
+    cache: u64,
+}
+
+#[stdto::bytes(endian = "big")]
+#[derive(ToHash)]
+struct Pair(u32, #[hash(skip)] u32);
+
+fn as_string<S: stdto::serde::Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[stdto::json]
+#[stdto::hash]
+struct Amount {
+    #[serde(serialize_with = "as_string")]
+    a: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[hash(skip)]
+    memo: String,
+}
+
+#[test]
+fn hash_skip_fields() {
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct View {
+        a: u32,
+        b: String,
+    }
+    let want = View {
+        a: 1,
+        b: "test".to_string(),
+    }
+    .to_hash::<Sha256>();
+
+    let signed = Signed {
+        a: 1,
+        b: "test".to_string(),
+        signature: vec![1, 2, 3],
+    };
+    assert_eq!(signed.to_hash::<Sha256>(), want);
+    assert_ne!(Sha256::digest(signed.to_bytes()), want);
+    assert_eq!(
+        signed.to_json(),
+        r#"{"a":1,"b":"test","signature":[1,2,3]}"#
+    );
+
+    let resigned = Signed {
+        signature: vec![4, 5, 6],
+        ..signed
+    };
+    assert_eq!(resigned.to_hash::<Sha256>(), want);
+    assert_eq!(
+        resigned.to_hash_with::<stdto::codec::Bincode, Sha256>(),
+        want
+    );
+    assert_eq!(
+        resigned.to_json_hash::<Sha256>(),
+        Sha256::digest(r#"{"a":1,"b":"test"}"#)
+    );
+    assert_eq!(
+        resigned.to_borsh_hash::<Sha256>(),
+        Sha256::digest([&1u32.to_le_bytes()[..], &4u32.to_le_bytes(), b"test"].concat())
+    );
+
+    // a reference hashes the view too
+    let view = View {
+        a: 1,
+        b: "test".to_string(),
+    };
+    assert_eq!(
+        stdto::hash_seq::<Sha256>([&resigned]),
+        stdto::hash_seq::<Sha256>([view])
+    );
+
+    let cached = Cached {
+        a: 1u32,
+        b: "test".to_string(),
+        cache: 7,
+    };
+    assert_eq!(cached.to_hash::<Sha256>(), want);
+
+    let pair = Pair(1, 2);
+    assert_eq!(pair.to_hash::<Sha256>(), Sha256::digest(1u32.to_be_bytes()));
+
+    // the view encodes fields like `to_json` does
+    let amount = Amount {
+        a: 5,
+        note: None,
+        memo: "lunch".to_string(),
+    };
+    assert_eq!(amount.to_json(), r#"{"a":"5","memo":"lunch"}"#);
+    assert_eq!(
+        amount.to_json_hash::<Sha256>(),
+        Sha256::digest(r#"{"a":"5"}"#)
+    );
+}
+
+#[test]
+fn format_hash() {
+    #[stdto::bytes]
+    #[stdto::hash]
+    #[derive(DebugHash, stdto::DisplayHash)]
+    #[debug(hasher = sha2::Sha256, mode = "lower0x", max_len = 8)]
+    #[display(hasher = sha2::Sha256, mode = "upper")]
+    struct Short {
+        a: u32,
+    }
+
+    #[stdto::bytes]
+    #[derive(DebugHex, stdto::DisplayHex)]
+    #[debug(max_len = 4)]
+    #[display(mode = "lower")]
+    struct Hexed {
+        a: u32,
+    }
+
+    let hash = Short { a: 1 }.to_hash::<Sha256>();
+    assert_eq!(
+        format!("{:?}", Short { a: 1 }),
+        format!("0x{}...", (&hash[..4]).to_hex())
+    );
+    assert_eq!(format!("{}", Short { a: 1 }), format!("{hash:X}"));
+
+    assert_eq!(format!("{:?}", Hexed { a: 1 }), "0x0100...");
+    assert_eq!(format!("{}", Hexed { a: 1 }), "01000000");
+}
+
+#[test]
+fn content_id() {
+    #[stdto::id(hasher = sha2::Sha256, len = 7)]
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct Commit {
+        message: String,
+    }
+
+    #[stdto::id(hasher = sha2::Sha256, format = "base32", len = 10)]
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct Blob(Vec<u8>);
+
+    #[stdto::id(hasher = sha2::Sha256, format = "base32")]
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct Tree(u32);
+
+    let commit = Commit {
+        message: "init".to_string(),
+    };
+    let hash = commit.to_hash::<Sha256>();
+    assert_eq!(commit.content_id(), hash.to_hex()[..7]);
+    assert_eq!(Blob(vec![1]).content_id().len(), 10);
+    assert_eq!(Tree(1).content_id().len(), 7);
+
+    let index = (0..100u8)
+        .map(|i| (Blob(vec![i]).to_hash::<Sha256>().to_hex(), i))
+        .collect::<stdto::id::ShortIdIndex<_>>();
+    let id = Blob(vec![42]).to_hash::<Sha256>().to_hex();
+    let short = index.abbreviate(&id, 4);
+    assert_eq!(index.resolve(short).unwrap(), (id.as_str(), &42));
+    assert!(matches!(
+        index.resolve(""),
+        Err(stdto::error::Error::AmbiguousId { .. })
+    ));
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn content_uuid() {
+    #[stdto::id(hasher = sha2::Sha256, format = "uuid")]
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct V8(u32);
+
+    #[stdto::id(hasher = sha2::Sha256, format = "uuid_v5", namespace = stdto::uuid::Uuid::NAMESPACE_URL)]
+    #[stdto::bytes]
+    #[stdto::hash]
+    struct V5(u32);
+
+    assert_eq!(V8(1).content_id().get_version_num(), 8);
+    assert_eq!(V8(1).content_id(), V8(1).content_id());
+    assert_ne!(V8(1).content_id(), V8(2).content_id());
+    assert_eq!(
+        V5(1).content_id(),
+        stdto::uuid::Uuid::new_v5(&stdto::uuid::Uuid::NAMESPACE_URL, &V5(1).to_hash::<Sha256>())
+    );
+}